@@ -0,0 +1,8 @@
+fn main()
+{
+    // Recorded so the runtime table can say which flags the times were
+    // measured with. Cargo separates the flags with 0x1f.
+    let flags = std::env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default().replace('\x1f', " ");
+    println!("cargo:rustc-env=AOC_RUSTFLAGS={}", flags);
+    println!("cargo:rerun-if-env-changed=CARGO_ENCODED_RUSTFLAGS");
+}
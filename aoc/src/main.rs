@@ -0,0 +1,524 @@
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use aoc_common::examples::{self, Example, Outcome};
+use aoc_common::{Answer, Bench, Error, Options, Solution, Source, Stats};
+use clap::{Parser, Subcommand};
+
+mod readme;
+use readme::Measurement;
+mod scaffold;
+mod verify;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Runs the Advent of Code 2019 solutions", long_about = None)]
+struct Cli
+{
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command
+{
+    /// Run the solution for one day, or every day
+    Run
+    {
+        #[arg(short, long, required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u32>,
+
+        /// Run every day that has a solution, one part after the other
+        #[arg(short, long, default_value_t=false)]
+        all: bool,
+
+        /// Longest a part may take with --all, in milliseconds
+        #[arg(short, long, default_value_t=readme::GOAL.as_millis() as u64, requires = "all")]
+        budget: u64,
+
+        /// Part to solve, both when left out
+        #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+
+        /// Check the part against the day's examples.txt, or use its
+        /// test_data.txt when it has none
+        #[arg(long, default_value_t=false)]
+        debug: bool,
+
+        /// Read the input from this file, relative to the current directory,
+        /// or - for stdin
+        #[arg(short, long, conflicts_with = "all")]
+        input: Option<String>,
+
+        /// Extra options passed on to the day, after --
+        #[arg(last = true, conflicts_with = "all")]
+        args: Vec<String>,
+    },
+    /// Time the solutions on their puzzle input
+    Bench
+    {
+        /// Days to time, all of them when left out
+        #[arg(short, long)]
+        day: Vec<u32>,
+
+        /// Part to time, both when left out
+        #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+
+        /// Measured runs of every part
+        #[arg(short, long, default_value_t=5, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+
+        /// Runs before measuring, which are not counted
+        #[arg(short, long, default_value_t=1)]
+        warm_up: usize,
+
+        /// Print the results as JSON instead of a table
+        #[arg(short, long, default_value_t=false)]
+        json: bool,
+
+        /// Rewrite the runtime table in the README with the mean times
+        #[arg(long, default_value_t=false)]
+        readme: bool,
+    },
+    /// Create the crate for a new day from template.rs
+    New
+    {
+        #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Check every day against the answers in its answers.txt
+    Verify
+    {
+        /// Days to check, all of them when left out
+        #[arg(short, long)]
+        day: Vec<u32>,
+    },
+}
+
+/// Days that have a solution.
+const DAYS: [u32; 11] = [1, 2, 3, 4, 5, 11, 13, 15, 17, 19, 21];
+
+/// Command line for the day, as if it was run on its own.
+fn day_args(day: u32, part: Option<u32>, debug: bool, input: Option<&str>, extra: &[String]) -> Vec<String>
+{
+    let mut args = vec![format!("day_{:02}", day)];
+    if let Some(part) = part
+    {
+        args.push("--part".to_string());
+        args.push(part.to_string());
+    }
+    if debug
+    {
+        args.push("--debug".to_string());
+    }
+    if let Some(input) = input
+    {
+        args.push("--input".to_string());
+        args.push(input.to_string());
+    }
+    args.extend(extra.iter().cloned());
+
+    args
+}
+
+fn run(day: u32, part: Option<u32>, debug: bool, input: Option<&str>, extra: &[String])
+{
+    let args = day_args(day, part, debug, input, extra);
+    match day
+    {
+        1 => aoc_common::run(&day_01::Day01, &Options::parse_from(args)),
+        2 => day_02::run(day_02::Args::parse_from(args)),
+        3 => aoc_common::run(&day_03::Day03, &Options::parse_from(args)),
+        4 => aoc_common::run(&day_04::Day04, &Options::parse_from(args)),
+        5 => day_05::run(day_05::Args::parse_from(args)),
+        11 => day_11::run(day_11::Args::parse_from(args)),
+        13 => day_13::run(day_13::Args::parse_from(args)),
+        15 => day_15::run(day_15::Args::parse_from(args)),
+        17 => day_17::run(day_17::Args::parse_from(args)),
+        19 => day_19::run(day_19::Args::parse_from(args)),
+        21 => day_21::run(day_21::Args::parse_from(args)),
+        _ => unreachable!(),
+    }
+}
+
+/// Solves one part on `source`, or with `debug` on every example of the
+/// part when the day has any, timing only the solving.
+fn solve_solution<S: Solution>(solution: &S, source: &Source, part: u32, debug: bool) -> Result<(Answer, Duration), Error>
+{
+    if debug
+        && let Some(examples) = examples::load(S::DIRECTORY)?
+    {
+        let mut duration = Duration::ZERO;
+        let mut count = 0;
+        for example in examples.into_iter().filter(|example| example.part == part)
+        {
+            let (answer, timing) = aoc_common::solve(solution, &example.input, part == 2)?;
+            if answer.to_string() != example.answer
+            {
+                return Err(Error::Solve(format!("example {} gave {}, expected {}", example.name, answer, example.answer)));
+            }
+            duration += timing.total();
+            count += 1;
+        }
+
+        return Ok((Answer::Text(format!("{} example(s) pass", count)), duration));
+    }
+
+    let (answer, timing) = aoc_common::solve(solution, source.read_bytes()?, part == 2)?;
+
+    Ok((answer, timing.total()))
+}
+
+/// Solves one part of a day without leaving the process, and returns the
+/// answer and how long solving it took, reading the input left out.
+fn solve_day(day: u32, part: u32, debug: bool) -> Result<(Answer, Duration), Error>
+{
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(format!("day_{:02}", day));
+    let source = Source::new(directory, None, debug);
+
+    match day
+    {
+        1 => solve_solution(&day_01::Day01, &source, part, debug),
+        2 => solve_solution(&day_02::Day02 { example: debug, magic_number: None }, &source, part, debug),
+        3 => solve_solution(&day_03::Day03, &source, part, debug),
+        4 => solve_solution(&day_04::Day04, &source, part, debug),
+        5 => solve_solution(&day_05::Day05 { debug, ..Default::default() }, &source, part, debug),
+        11 => solve_solution(&day_11::Day11::default(), &source, part, debug),
+        13 => solve_solution(&day_13::Day13::default(), &source, part, debug),
+        15 => solve_solution(&day_15::Day15::default(), &source, part, debug),
+        17 => solve_solution(&day_17::Day17::default(), &source, part, debug),
+        19 => solve_solution(&day_19::Day19::default(), &source, part, debug),
+        21 => solve_solution(&day_21::Day21::default(), &source, part, debug),
+        _ => unreachable!(),
+    }
+}
+
+/// Whether the day has a puzzle input in its data.txt.
+fn has_input(day: u32) -> bool
+{
+    let data = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(format!("day_{:02}", day)).join("data.txt");
+
+    fs::metadata(data).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+/// Runs every part of every day in turn, skipping days without a puzzle
+/// input, and then prints how long solving each took. A part that fails
+/// is reported and the rest still run. Exits with 1 if any part failed or
+/// took longer than `budget`.
+fn run_all(part: Option<u32>, debug: bool, budget: Duration)
+{
+    let parts = match part
+    {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let mut times = vec![];
+    let mut failed = vec![];
+    for day in DAYS
+    {
+        if !debug && !has_input(day)
+        {
+            println!("Day {}: no puzzle input in data.txt, skipped", day);
+            continue;
+        }
+
+        for &part in &parts
+        {
+            match solve_day(day, part, debug)
+            {
+                Ok((answer, duration)) =>
+                {
+                    // Answers drawn as text start on a line of their own
+                    let answer = answer.to_string();
+                    let separator = if answer.contains('\n') { "\n" } else { " " };
+                    println!("Day {} part {}:{}{}", day, part, separator, answer.trim_end());
+                    times.push((day, part, duration));
+                }
+                Err(e) =>
+                {
+                    println!("Day {} part {}: failed, {}", day, part, e);
+                    failed.push(format!("day {} part {}", day, part));
+                }
+            }
+        }
+    }
+
+    println!();
+    let mut over = 0;
+    for &(day, part, duration) in &times
+    {
+        let note = if duration > budget
+        {
+            over += 1;
+            " over budget"
+        }
+        else
+        {
+            ""
+        };
+        println!("Day {:>2} part {}: {:>12.3?}{}", day, part, duration, note);
+    }
+
+    let total: Duration = times.iter().map(|&(_, _, duration)| duration).sum();
+    let goal = readme::GOAL * times.len() as u32;
+    println!("Total: {:.3?} for {} parts, the goal is {:?} per part, {:?} in all", total, times.len(), readme::GOAL, goal);
+    if !failed.is_empty()
+    {
+        eprintln!("Error: {} part(s) failed, {}", failed.len(), failed.join(", "));
+    }
+    if over > 0
+    {
+        eprintln!("Error: {} part(s) took longer than the budget of {:?}", over, budget);
+    }
+    if !failed.is_empty() || over > 0
+    {
+        process::exit(1);
+    }
+}
+
+fn bench_day(day: u32, part_two: bool, runs: usize, warm_up: usize) -> Result<Bench, Error>
+{
+    match day
+    {
+        1 => aoc_common::bench(&day_01::Day01, part_two, runs, warm_up),
+        2 => aoc_common::bench(&day_02::Day02 { example: false, magic_number: None }, part_two, runs, warm_up),
+        3 => aoc_common::bench(&day_03::Day03, part_two, runs, warm_up),
+        4 => aoc_common::bench(&day_04::Day04, part_two, runs, warm_up),
+        5 => aoc_common::bench(&day_05::Day05::default(), part_two, runs, warm_up),
+        11 => aoc_common::bench(&day_11::Day11::default(), part_two, runs, warm_up),
+        13 => aoc_common::bench(&day_13::Day13::default(), part_two, runs, warm_up),
+        15 => aoc_common::bench(&day_15::Day15::default(), part_two, runs, warm_up),
+        17 => aoc_common::bench(&day_17::Day17::default(), part_two, runs, warm_up),
+        19 => aoc_common::bench(&day_19::Day19::default(), part_two, runs, warm_up),
+        21 => aoc_common::bench(&day_21::Day21::default(), part_two, runs, warm_up),
+        _ => unreachable!(),
+    }
+}
+
+fn check_examples(day: u32, examples: Vec<Example>) -> Vec<Outcome>
+{
+    match day
+    {
+        1 => examples::check(&day_01::Day01, examples),
+        2 => examples::check(&day_02::Day02 { example: true, magic_number: None }, examples),
+        3 => examples::check(&day_03::Day03, examples),
+        4 => examples::check(&day_04::Day04, examples),
+        5 => examples::check(&day_05::Day05 { debug: true, ..Default::default() }, examples),
+        11 => examples::check(&day_11::Day11::default(), examples),
+        13 => examples::check(&day_13::Day13::default(), examples),
+        15 => examples::check(&day_15::Day15::default(), examples),
+        17 => examples::check(&day_17::Day17::default(), examples),
+        19 => examples::check(&day_19::Day19::default(), examples),
+        21 => examples::check(&day_21::Day21::default(), examples),
+        _ => unreachable!(),
+    }
+}
+
+fn stats_line(name: &str, stats: &Stats) -> String
+{
+    format!("  {:<5} mean {:>10.3?}  median {:>10.3?}  min {:>10.3?}  std dev {:>10.3?}",
+        name, stats.mean, stats.median, stats.min, stats.std_dev)
+}
+
+fn bench(days: &[u32], part: Option<u32>, runs: usize, warm_up: usize, json: bool, update_readme: bool)
+{
+    let days = if days.is_empty() { DAYS.into_iter().filter(|&day| has_input(day)).collect() } else { days.to_vec() };
+    if let Some(day) = days.iter().find(|day| !DAYS.contains(day))
+    {
+        eprintln!("Error: there is no solution for day {}, try one of {:?}", day, DAYS);
+        process::exit(1);
+    }
+    let parts = match part
+    {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    let mut results = vec![];
+    let mut measurements = vec![];
+    for &day in &days
+    {
+        for &part in &parts
+        {
+            let result = match bench_day(day, part == 2, runs, warm_up)
+            {
+                Ok(result) => result,
+                Err(e) =>
+                {
+                    eprintln!("Error: day {} part {} failed, {}", day, part, e);
+                    process::exit(1);
+                }
+            };
+            if !json
+            {
+                println!("Day {} part {}: {} ({} runs)", day, part, result.answer, result.runs);
+                println!("{}", stats_line("parse", &result.parse));
+                println!("{}", stats_line("solve", &result.solve));
+                println!("{}", stats_line("total", &result.total));
+            }
+            if result.total.mean > readme::GOAL
+            {
+                eprintln!("Warning: day {} part {} takes {:.3?}, over the {:?} goal", day, part, result.total.mean, readme::GOAL);
+            }
+            measurements.push(Measurement { day, part, mean: result.total.mean });
+            results.push(format!("{{\"day\":{},\"part\":{},{}", day, part, &result.to_json()[1..]));
+        }
+    }
+
+    if json
+    {
+        println!("[{}]", results.join(","));
+    }
+
+    if update_readme
+    {
+        write_readme(&measurements);
+    }
+}
+
+fn write_readme(measurements: &[Measurement])
+{
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("README.md");
+    let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+
+    let result = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| readme::update_table(&text, measurements, profile, env!("AOC_RUSTFLAGS")))
+        .and_then(|text| fs::write(&path, text).map_err(|e| e.to_string()));
+    if let Err(e) = result
+    {
+        eprintln!("Error: could not update {}, {}", path.display(), e);
+        process::exit(1);
+    }
+}
+
+/// Checks the days against their known answers and their examples,
+/// printing a diff of the mismatches, and exits with 1 if there are any.
+fn verify(days: &[u32])
+{
+    let days = if days.is_empty() { DAYS.to_vec() } else { days.to_vec() };
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+
+    let mut checked = 0;
+    let mut failed = 0;
+    let mut missing = vec![];
+    for &day in &days
+    {
+        let name = format!("day_{:02}/{}", day, verify::ANSWERS_FILE_PATH);
+        let path = root.join(&name);
+        let answers = match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| verify::parse(&text))
+        {
+            Ok(answers) => answers,
+            Err(e) =>
+            {
+                eprintln!("Error: could not read {}, {}", name, e);
+                process::exit(1);
+            }
+        };
+        if !answers.iter().any(|expected| expected.input == "data.txt")
+        {
+            missing.push(day.to_string());
+        }
+
+        for expected in answers
+        {
+            checked += 1;
+            let actual = verify::answer(day, &expected);
+            if actual.as_ref() == Ok(&expected.answer)
+            {
+                continue;
+            }
+
+            failed += 1;
+            println!("--- {}", name);
+            println!("- {}", expected);
+            match actual
+            {
+                Ok(answer) => println!("+ {} {} {}", expected.input, expected.part, answer),
+                Err(e) => println!("+ {} {} failed: {}", expected.input, expected.part, e),
+            }
+        }
+
+        let directory = root.join(format!("day_{:02}", day));
+        let examples = match examples::load(&directory)
+        {
+            Ok(Some(examples)) => examples,
+            Ok(_) => continue,
+            Err(e) =>
+            {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        };
+        for outcome in check_examples(day, examples)
+        {
+            checked += 1;
+            if outcome.passed()
+            {
+                continue;
+            }
+
+            failed += 1;
+            let example = &outcome.example;
+            println!("--- day_{:02}/{}", day, examples::EXAMPLES_FILE_PATH);
+            println!("- {} {} {}", example.name, example.part, example.answer);
+            match outcome.actual
+            {
+                Ok(answer) => println!("+ {} {} {}", example.name, example.part, answer),
+                Err(e) => println!("+ {} {} failed: {}", example.name, example.part, e),
+            }
+        }
+    }
+
+    println!("{} of {} answers match", checked - failed, checked);
+    if !missing.is_empty()
+    {
+        println!("No data.txt answers for day(s) {}", missing.join(", "));
+    }
+    if failed > 0
+    {
+        process::exit(1);
+    }
+}
+
+fn main()
+{
+    let cli = Cli::parse();
+
+    match cli.command
+    {
+        Command::Run { day: None, part, debug, budget, .. } => run_all(part, debug, Duration::from_millis(budget)),
+        Command::Run { day: Some(day), part, debug, input, args, .. } =>
+        {
+            if !DAYS.contains(&day)
+            {
+                eprintln!("Error: there is no solution for day {}, try one of {:?}", day, DAYS);
+                process::exit(1);
+            }
+            run(day, part, debug, input.as_deref(), &args);
+        }
+        Command::Bench { day, part, runs, warm_up, json, readme } => bench(&day, part, runs as usize, warm_up, json, readme),
+        Command::New { day } =>
+        {
+            let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+            if let Err(e) = scaffold::create(&root, day)
+            {
+                eprintln!("Error: could not create day {}, {}", day, e);
+                process::exit(1);
+            }
+            println!("Created day_{:02}, run it with aoc run --day {}", day, day);
+        }
+        Command::Verify { day } =>
+        {
+            if let Some(day) = day.iter().find(|day| !DAYS.contains(day))
+            {
+                eprintln!("Error: there is no solution for day {}, try one of {:?}", day, DAYS);
+                process::exit(1);
+            }
+            verify(&day);
+        }
+    }
+}
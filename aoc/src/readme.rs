@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+pub const START_MARKER: &str = "<!-- runtimes:start -->";
+pub const END_MARKER: &str = "<!-- runtimes:end -->";
+
+/// The goal every part has to stay under.
+pub const GOAL: Duration = Duration::from_secs(1);
+
+const DAYS: u32 = 25;
+const HEADER: &str = "| Day | Part 1 | Part 2 | Profile | RUSTFLAGS |\n|-----|--------|--------|---------|-----------|";
+
+/// One part's mean runtime.
+pub struct Measurement
+{
+    pub day: u32,
+    pub part: u32,
+    pub mean: Duration,
+}
+
+/// Cell for a mean runtime, pointing out the ones over the goal.
+fn cell(mean: Duration) -> String
+{
+    if mean > GOAL
+    {
+        format!("**{:.3?}** (over {:?})", mean, GOAL)
+    }
+    else
+    {
+        format!("{:.3?}", mean)
+    }
+}
+
+/// The cells of every row in the current table, by day, so the days that
+/// were not measured keep their times.
+fn rows(table: &str) -> Vec<Vec<String>>
+{
+    let mut rows: Vec<Vec<String>> = (1..=DAYS).map(|day| vec![day.to_string(), "-".to_string(), "-".to_string(), String::new(), String::new()]).collect();
+    for line in table.lines()
+    {
+        let cells: Vec<String> = line.trim().trim_matches('|').split('|').map(|cell| cell.trim().to_string()).collect();
+        if let Some(Ok(day)) = cells.first().map(|cell| cell.parse::<u32>())
+            && (1..=DAYS).contains(&day)
+        {
+            let row = &mut rows[day as usize - 1];
+            for (index, value) in cells.into_iter().enumerate().skip(1).take(4)
+            {
+                row[index] = value;
+            }
+        }
+    }
+
+    rows
+}
+
+/// Rewrites the runtime table between the markers in `readme` with the
+/// measured parts, built with `profile` and `rustflags`.
+pub fn update_table(readme: &str, measurements: &[Measurement], profile: &str, rustflags: &str) -> Result<String, String>
+{
+    let start = readme.find(START_MARKER).ok_or(format!("there is no {} marker", START_MARKER))? + START_MARKER.len();
+    let end = start + readme[start..].find(END_MARKER).ok_or(format!("there is no {} marker after the start", END_MARKER))?;
+
+    let mut rows = rows(&readme[start..end]);
+    for measurement in measurements
+    {
+        let row = &mut rows[measurement.day as usize - 1];
+        row[measurement.part as usize] = cell(measurement.mean);
+        row[3] = profile.to_string();
+        row[4] = rustflags.to_string();
+    }
+
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| if cell.is_empty() { " ".to_string() } else { format!(" {} ", cell) }).collect::<Vec<_>>().join("|"))
+        .map(|line| format!("|{}|", line))
+        .collect();
+
+    Ok(format!("{}\n{}\n{}\n{}{}", &readme[..start], HEADER, lines.join("\n"), END_MARKER, &readme[end + END_MARKER.len()..]))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_update_table()
+    {
+        let readme = format!("Intro\n{}\n| 1 | 5ms | 6ms | release | |\n| 3 | 65ms | 73ms | release | -C target-cpu=native |\n{}\nOutro\n", START_MARKER, END_MARKER);
+        let measurements = [Measurement { day: 3, part: 2, mean: Duration::from_millis(1500) }];
+
+        let updated = update_table(&readme, &measurements, "release", "").unwrap();
+        let lines: Vec<&str> = updated.lines().collect();
+
+        assert_eq!(lines[0], "Intro");
+        assert_eq!(lines[4], "| 1 | 5ms | 6ms | release | |");
+        assert_eq!(lines[5], "| 2 | - | - | | |");
+        assert_eq!(lines[6], "| 3 | 65ms | **1.500s** (over 1s) | release | |");
+        assert_eq!(lines[29], END_MARKER);
+        assert_eq!(lines[30], "Outro");
+    }
+}
@@ -0,0 +1,283 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::readme;
+
+/// The `Solution` stub every new day starts from, with `Day00` and
+/// `day_00` standing in for the day.
+const TEMPLATE: &str = include_str!("../../template.rs");
+
+const MAIN: &str = "use aoc_common::Options;
+use clap::Parser;
+
+fn main()
+{
+    aoc_common::run(&day_00::Day00, &Options::parse());
+}
+";
+
+const MANIFEST: &str = "[package]
+name = \"day_00\"
+version = \"0.1.0\"
+edition = \"2024\"
+
+[dependencies]
+aoc_common = { path = \"../common\" }
+clap = { version = \"4.6\", features = [\"derive\"] }
+";
+
+const EXAMPLES: &str = "# Examples from the puzzle text, each case is a header
+# '== <name> <part> <answer>' followed by its input. Headers directly after
+# each other share the input below them.
+";
+
+const ANSWERS: &str = "# <input> <part> <answer>\n";
+
+/// The day whose arms in the runner's dispatch a new day copies, as it
+/// only implements `Solution` and takes no options of its own.
+const MODEL_DAY: u32 = 4;
+
+fn fill(template: &str, day: u32) -> String
+{
+    template.replace("day_00", &format!("day_{:02}", day)).replace("Day00", &format!("Day{:02}", day))
+}
+
+/// Adds `name` to the members of the workspace manifest, keeping the days
+/// in order after the shared crates.
+pub fn register(manifest: &str, name: &str) -> Result<String, String>
+{
+    let start = manifest.find("members = [").ok_or("there is no members list")? + "members = [".len();
+    let end = start + manifest[start..].find(']').ok_or("the members list is not closed")?;
+
+    let mut members: Vec<String> = manifest[start..end]
+        .split(',')
+        .map(|member| member.trim().trim_matches('"').to_string())
+        .filter(|member| !member.is_empty())
+        .collect();
+    if members.iter().any(|member| member == name)
+    {
+        return Err(format!("{} is already a member", name));
+    }
+    members.push(name.to_string());
+    members.sort_by_key(|member| (member.starts_with("day_"), member.clone()));
+
+    let lines: Vec<String> = members.iter().map(|member| format!("    \"{}\",", member)).collect();
+
+    Ok(format!("{}\n{}\n{}", &manifest[..start], lines.join("\n"), &manifest[end..]))
+}
+
+/// Adds `name` as a path dependency of the runner, keeping the days in
+/// order.
+pub fn add_dependency(manifest: &str, name: &str) -> Result<String, String>
+{
+    let mut lines: Vec<&str> = manifest.lines().collect();
+    let days: Vec<usize> = (0..lines.len()).filter(|&index| lines[index].starts_with("day_")).collect();
+    if days.iter().any(|&index| lines[index].starts_with(&format!("{} ", name)))
+    {
+        return Err(format!("{} is already a dependency", name));
+    }
+
+    let last = *days.last().ok_or("there are no day dependencies")?;
+    let index = days.into_iter().find(|&index| lines[index] > name).unwrap_or(last + 1);
+    let line = format!("{} = {{ path = \"../{}\" }}", name, name);
+    lines.insert(index, &line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `day` to the list in `const <constant>: [u32; <length>] = [...];`.
+fn add_to_list(main: &str, constant: &str, day: u32) -> Result<String, String>
+{
+    let declaration = format!("const {}: [u32; ", constant);
+    let start = main.find(&declaration).ok_or(format!("there is no {}", constant))?;
+    let end = start + main[start..].find("];").ok_or(format!("{} is not closed", constant))?;
+    let list_start = start + main[start..end].find("= [").ok_or(format!("{} is not a list", constant))? + "= [".len();
+
+    let mut days = main[list_start..end]
+        .split(',')
+        .map(|day| day.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("could not read {}, {}", constant, e))?;
+    days.push(day);
+    days.sort();
+    let days: Vec<String> = days.iter().map(|day| day.to_string()).collect();
+
+    Ok(format!("{}{}{}] = [{}{}", &main[..start], declaration, days.len(), days.join(", "), &main[end..]))
+}
+
+/// Adds `day` to `DAYS` in the runner, and gives it a copy of every
+/// dispatch arm of the model day, in order before the arms of later days or
+/// the one that catches the rest.
+pub fn add_to_runner(main: &str, day: u32) -> Result<String, String>
+{
+    let main = add_to_list(main, "DAYS", day)?;
+
+    let model = format!("day_{:02}::Day{:02}", MODEL_DAY, MODEL_DAY);
+    let mut lines = vec![];
+    let mut arm = None;
+    let mut arms = 0;
+    for line in main.lines()
+    {
+        let pattern = line.split_whitespace().next().unwrap_or("");
+        let later = pattern == "_" || pattern.parse::<u32>().is_ok_and(|other| other > day);
+        if later
+            && let Some(arm) = arm.take()
+        {
+            lines.push(arm);
+            arms += 1;
+        }
+        if line.contains(&model)
+        {
+            let copy = line.replacen(&format!("{} =>", MODEL_DAY), &format!("{} =>", day), 1);
+            arm = Some(copy.replace(&format!("day_{:02}", MODEL_DAY), &format!("day_{:02}", day))
+                .replace(&format!("Day{:02}", MODEL_DAY), &format!("Day{:02}", day)));
+        }
+        lines.push(line.to_string());
+    }
+    if arms == 0
+    {
+        return Err(format!("there is no dispatch arm for day {} to copy", MODEL_DAY));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Reads the file at `path` in `root` and works out its new text.
+fn edit(root: &Path, path: &str, change: impl FnOnce(&str) -> Result<String, String>) -> Result<(PathBuf, String, String), String>
+{
+    let path = root.join(path);
+    let original = fs::read_to_string(&path).map_err(|e| format!("could not read {}, {}", path.display(), e))?;
+    let changed = change(&original).map_err(|e| format!("could not update {}, {}", path.display(), e))?;
+
+    Ok((path, original, changed))
+}
+
+/// Writes the files of a new day to `directory`.
+fn write_day(directory: &Path, day: u32) -> Result<(), String>
+{
+    let files = [
+        ("Cargo.toml", fill(MANIFEST, day)),
+        ("src/lib.rs", fill(TEMPLATE, day)),
+        ("src/main.rs", fill(MAIN, day)),
+        ("examples.txt", EXAMPLES.to_string()),
+        ("answers.txt", ANSWERS.to_string()),
+        ("data.txt", String::new()),
+    ];
+    fs::create_dir_all(directory.join("src")).map_err(|e| e.to_string())?;
+    for (path, text) in files
+    {
+        fs::write(directory.join(path), text).map_err(|e| format!("could not write {}, {}", path, e))?;
+    }
+
+    Ok(())
+}
+
+/// Creates the crate for `day` in `root` from the template, registers it in
+/// the workspace and the runner and makes sure the README has a row for it.
+/// Refuses to touch a day that already exists. The crate is written to a
+/// staging directory that is only moved into place once it is complete,
+/// and if updating any of the other files fails the ones already written
+/// are put back, so a failure leaves the tree as it was.
+pub fn create(root: &Path, day: u32) -> Result<(), String>
+{
+    let name = format!("day_{:02}", day);
+    let directory = root.join(&name);
+    if directory.exists()
+    {
+        return Err(format!("{} already exists", name));
+    }
+
+    let edits = [
+        edit(root, "Cargo.toml", |text| register(text, &name))?,
+        edit(root, "README.md", |text| readme::update_table(text, &[], "", ""))?,
+        edit(root, "aoc/Cargo.toml", |text| add_dependency(text, &name))?,
+        edit(root, "aoc/src/main.rs", |text| add_to_runner(text, day))?,
+    ];
+
+    let staging = root.join(format!(".{}.new", name));
+    if staging.exists()
+    {
+        fs::remove_dir_all(&staging).map_err(|e| format!("could not remove {}, {}", staging.display(), e))?;
+    }
+    if let Err(e) = write_day(&staging, day).and_then(|_| fs::rename(&staging, &directory).map_err(|e| e.to_string()))
+    {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!("could not create {}, {}", name, e));
+    }
+
+    for (index, (path, _, changed)) in edits.iter().enumerate()
+    {
+        if let Err(e) = fs::write(path, changed)
+        {
+            for (path, original, _) in &edits[..index]
+            {
+                let _ = fs::write(path, original);
+            }
+            let _ = fs::remove_dir_all(&directory);
+            return Err(format!("could not write {}, {}", path.display(), e));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_register()
+    {
+        let manifest = "[workspace]\nmembers = [\n    \"aoc\",\n    \"day_01\",\n    \"day_11\",\n]\n";
+        let expected = "[workspace]\nmembers = [\n    \"aoc\",\n    \"day_01\",\n    \"day_07\",\n    \"day_11\",\n]\n";
+
+        assert_eq!(register(manifest, "day_07").unwrap(), expected);
+        assert!(register(manifest, "day_11").is_err());
+    }
+
+    #[test]
+    fn test_add_dependency()
+    {
+        let manifest = "[dependencies]\nclap = \"4.6\"\nday_01 = { path = \"../day_01\" }\nday_11 = { path = \"../day_11\" }\nintcode = { path = \"../intcode\" }\n";
+        let expected = "[dependencies]\nclap = \"4.6\"\nday_01 = { path = \"../day_01\" }\nday_07 = { path = \"../day_07\" }\nday_11 = { path = \"../day_11\" }\nintcode = { path = \"../intcode\" }\n";
+
+        assert_eq!(add_dependency(manifest, "day_07").unwrap(), expected);
+        assert_eq!(add_dependency(manifest, "day_12").unwrap().lines().nth(4), Some("day_12 = { path = \"../day_12\" }"));
+        assert!(add_dependency(manifest, "day_11").is_err());
+    }
+
+    #[test]
+    fn test_add_to_runner()
+    {
+        let main = "const DAYS: [u32; 3] = [1, 4, 11];
+
+fn run(day: u32)
+{
+    match day
+    {
+        4 => aoc_common::run(&day_04::Day04, &Options::parse_from(args)),
+        11 => day_11::run(day_11::Args::parse_from(args)),
+        _ => unreachable!(),
+    }
+}
+";
+        let expected = "const DAYS: [u32; 4] = [1, 4, 7, 11];
+
+fn run(day: u32)
+{
+    match day
+    {
+        4 => aoc_common::run(&day_04::Day04, &Options::parse_from(args)),
+        7 => aoc_common::run(&day_07::Day07, &Options::parse_from(args)),
+        11 => day_11::run(day_11::Args::parse_from(args)),
+        _ => unreachable!(),
+    }
+}
+";
+
+        assert_eq!(add_to_runner(main, 7).unwrap(), expected);
+        assert!(add_to_runner(main, 12).unwrap().contains("12 => aoc_common::run(&day_12::Day12, &Options::parse_from(args)),\n        _ => unreachable!(),"));
+        assert!(add_to_runner("const DAYS: [u32; 1] = [1];\n", 7).is_err());
+    }
+}
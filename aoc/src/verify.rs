@@ -0,0 +1,101 @@
+use std::env;
+use std::fmt::Display;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Name of the file in a day's directory with its known answers.
+pub const ANSWERS_FILE_PATH: &str = "answers.txt";
+
+/// One known answer, a line '<input> <part> <answer>' in the answers file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expected
+{
+    pub input: String,
+    pub part: u32,
+    pub answer: String,
+}
+
+impl Display for Expected
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "{} {} {}", self.input, self.part, self.answer)
+    }
+}
+
+/// Reads an answers file, skipping blank lines and lines starting with #.
+pub fn parse(text: &str) -> Result<Vec<Expected>, String>
+{
+    let mut answers = vec![];
+    for (index, line) in text.lines().enumerate()
+    {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#')
+        {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.splitn(3, ' ').collect();
+        match fields[..]
+        {
+            [input, part @ ("1" | "2"), answer] => answers.push(Expected
+            {
+                input: input.to_string(),
+                part: part.parse().unwrap(),
+                answer: answer.trim().to_string(),
+            }),
+            _ => return Err(format!("line {}: expected '<input> <part> <answer>', got '{}'", index + 1, line)),
+        }
+    }
+
+    Ok(answers)
+}
+
+/// Runs one day and part through this binary, the same way `aoc run` does,
+/// and returns the answer it printed. test_data.txt runs in debug mode, as
+/// some days treat the example differently.
+pub fn answer(day: u32, expected: &Expected) -> Result<String, String>
+{
+    let executable = env::current_exe().map_err(|e| e.to_string())?;
+    let mut command = Command::new(executable);
+    command.args(["run", "--day", &day.to_string(), "--part", &expected.part.to_string()]);
+    match expected.input.as_str()
+    {
+        "data.txt" => (),
+        "test_data.txt" => { command.arg("--debug"); }
+        input =>
+        {
+            // Inputs are listed relative to the day's directory
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(format!("day_{:02}", day)).join(input);
+            command.arg("--input").arg(path);
+        }
+    }
+
+    let output = command.stdin(Stdio::null()).output().map_err(|e| e.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().rev().find_map(|line| line.strip_prefix("The result is: "))
+    {
+        Some(answer) => Ok(answer.trim().to_string()),
+        None =>
+        {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(stderr.lines().last().unwrap_or("no answer was printed").to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_parse()
+    {
+        let answers = parse("# input part answer\ndata.txt 1 42\n\ntest_data.txt 2 HELLO WORLD\n").unwrap();
+
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[1], Expected { input: "test_data.txt".to_string(), part: 2, answer: "HELLO WORLD".to_string() });
+        assert!(parse("data.txt 3 42").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use crate::input::Source;
+use crate::solution::{solve, Answer, Error, Solution};
+
+/// Summary of the times of a number of runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats
+{
+    pub mean: Duration,
+    pub median: Duration,
+    pub min: Duration,
+    /// Population standard deviation.
+    pub std_dev: Duration,
+}
+
+impl Stats
+{
+    /// Panics on no times, there is always at least one run.
+    pub fn new(times: &[Duration]) -> Self
+    {
+        let mut sorted = times.to_vec();
+        sorted.sort();
+
+        let count = sorted.len() as f64;
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / count;
+        let variance = sorted.iter().map(|time| (time.as_secs_f64() - mean).powi(2)).sum::<f64>() / count;
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) { (sorted[middle - 1] + sorted[middle]) / 2 } else { sorted[middle] };
+
+        Stats
+        {
+            mean: Duration::from_secs_f64(mean),
+            median,
+            min: sorted[0],
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    pub fn to_json(&self) -> String
+    {
+        format!("{{\"mean_ns\":{},\"median_ns\":{},\"min_ns\":{},\"std_dev_ns\":{}}}",
+            self.mean.as_nanos(), self.median.as_nanos(), self.min.as_nanos(), self.std_dev.as_nanos())
+    }
+}
+
+/// The measured runs of one part, parsing and solving timed apart.
+#[derive(Clone, Debug)]
+pub struct Bench
+{
+    pub answer: Answer,
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+    pub total: Stats,
+}
+
+impl Bench
+{
+    pub fn to_json(&self) -> String
+    {
+        format!("{{\"answer\":{},\"runs\":{},\"parse\":{},\"solve\":{},\"total\":{}}}",
+            self.answer.to_json(), self.runs, self.parse.to_json(), self.solve.to_json(), self.total.to_json())
+    }
+}
+
+/// Solves one part on the puzzle input `runs` times, after `warm_up` runs
+/// that are not measured. The input file is only read once, parsing it is
+/// part of every run.
+pub fn bench<S: Solution>(solution: &S, part_two: bool, runs: usize, warm_up: usize) -> Result<Bench, Error>
+{
+    let input = Source::new(S::DIRECTORY, None, false).read_bytes()?;
+
+    for _ in 0..warm_up
+    {
+        solve(solution, &input, part_two)?;
+    }
+
+    let mut answer = None;
+    let mut parse = vec![];
+    let mut solving = vec![];
+    for _ in 0..runs
+    {
+        let (result, timing) = solve(solution, &input, part_two)?;
+        if answer.as_ref().is_some_and(|answer| *answer != result)
+        {
+            return Err(Error::Solve(format!("the answer changed between runs, {} and {}", answer.unwrap(), result)));
+        }
+        answer = Some(result);
+        parse.push(timing.parse);
+        solving.push(timing.solve);
+    }
+
+    let total: Vec<Duration> = parse.iter().zip(&solving).map(|(parse, solve)| *parse + *solve).collect();
+
+    Ok(Bench
+    {
+        answer: answer.ok_or_else(|| Error::Solve("there has to be at least one run".to_string()))?,
+        runs,
+        parse: Stats::new(&parse),
+        solve: Stats::new(&solving),
+        total: Stats::new(&total),
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_stats()
+    {
+        let times: Vec<Duration> = [4, 1, 3, 2].iter().map(|&ms| Duration::from_millis(ms)).collect();
+        let stats = Stats::new(&times);
+
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.std_dev.as_micros(), 1118);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::input::InputError;
+use crate::solution::{solve, Answer, Error, Solution};
+
+/// Name of the file in a day's directory with the examples from the puzzle.
+pub const EXAMPLES_FILE_PATH: &str = "examples.txt";
+
+/// Starts the header of a case, '== <name> <part> <answer>'.
+const HEADER: &str = "== ";
+
+/// One example from the puzzle text, with the answer it should give.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example
+{
+    pub name: String,
+    pub part: u32,
+    pub answer: String,
+    pub input: String,
+}
+
+/// An example and what the solution answered.
+#[derive(Debug)]
+pub struct Outcome
+{
+    pub example: Example,
+    pub actual: Result<Answer, Error>,
+}
+
+impl Outcome
+{
+    pub fn passed(&self) -> bool
+    {
+        matches!(&self.actual, Ok(answer) if answer.to_string() == self.example.answer)
+    }
+}
+
+impl Display for Outcome
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "Example {} part {}: ", self.example.name, self.example.part)?;
+        match &self.actual
+        {
+            Ok(_) if self.passed() => write!(f, "{} ok", self.example.answer),
+            Ok(answer) => write!(f, "expected {}, got {} FAILED", self.example.answer, answer),
+            Err(e) => write!(f, "expected {}, failed with {} FAILED", self.example.answer, e),
+        }
+    }
+}
+
+/// Reads a sectioned examples file. Every case starts with a header line
+/// '== <name> <part> <answer>' followed by its input, and headers directly
+/// after each other share the input below them. Lines before the first
+/// header are comments.
+pub fn parse(text: &str) -> Result<Vec<Example>, InputError>
+{
+    let mut examples = vec![];
+    let mut headers: Vec<Example> = vec![];
+    let mut input = String::new();
+    let mut has_input = false;
+
+    for (index, line) in text.lines().enumerate()
+    {
+        if let Some(header) = line.strip_prefix(HEADER)
+        {
+            if has_input
+            {
+                examples.extend(headers.drain(..).map(|example| Example { input: input.clone(), ..example }));
+                input.clear();
+                has_input = false;
+            }
+
+            let fields: Vec<&str> = header.splitn(3, ' ').collect();
+            match fields[..]
+            {
+                [name, part @ ("1" | "2"), answer] => headers.push(Example
+                {
+                    name: name.to_string(),
+                    part: part.parse().unwrap(),
+                    answer: answer.trim().to_string(),
+                    input: String::new(),
+                }),
+                _ => return Err(InputError::Example(index + 1, line.to_string())),
+            }
+        }
+        else if !headers.is_empty()
+        {
+            input.push_str(line);
+            input.push('\n');
+            has_input = true;
+        }
+    }
+    examples.extend(headers.drain(..).map(|example| Example { input: input.clone(), ..example }));
+
+    Ok(examples)
+}
+
+/// The examples of the day in `directory`, or None when it has none.
+pub fn load(directory: impl AsRef<Path>) -> Result<Option<Vec<Example>>, InputError>
+{
+    let path = directory.as_ref().join(EXAMPLES_FILE_PATH);
+    match fs::read_to_string(&path)
+    {
+        Ok(text) => Ok(Some(parse(&text)?)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(InputError::Io(path.display().to_string(), e)),
+    }
+}
+
+/// Solves every example with the part it is for.
+pub fn check<S: Solution>(solution: &S, examples: Vec<Example>) -> Vec<Outcome>
+{
+    examples
+        .into_iter()
+        .map(|example|
+        {
+            let actual = solve(solution, &example.input, example.part == 2).map(|(answer, _)| answer);
+            Outcome { example, actual }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_parse()
+    {
+        let text = "# <name> <part> <answer>\n== small 1 2\n== small 2 2\n14\n== large 1 33583\n100756\n";
+        let examples = parse(text).unwrap();
+
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[1], Example { name: "small".to_string(), part: 2, answer: "2".to_string(), input: "14\n".to_string() });
+        assert_eq!(examples[2].input, "100756\n");
+        assert!(matches!(parse("== small 3 2\n14\n"), Err(InputError::Example(1, _))));
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const DATA_FILE_PATH: &str = "data.txt";
+const TEST_DATA_FILE_PATH: &str = "test_data.txt";
+
+#[derive(Debug)]
+pub enum InputError
+{
+    /// The input could not be read, with where it was read from.
+    Io(String, io::Error),
+    /// A line that is not a number, 1-based.
+    Number(usize, String),
+    /// A comma separated value that is not a number, 1-based.
+    Value(usize, String),
+    /// A grid row that is not as wide as the first one, 1-based.
+    Row(usize, usize, usize),
+    /// Text that is not a range 'a-b'.
+    Range(String),
+    /// An examples file header that is not '== <name> <part> <answer>',
+    /// 1-based.
+    Example(usize, String),
+    Empty,
+}
+
+impl Display for InputError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            InputError::Io(source, e) => write!(f, "could not read {}, {}", source, e),
+            InputError::Number(line, text) => write!(f, "line {}: '{}' is not a number", line, text),
+            InputError::Value(index, text) => write!(f, "value {}: '{}' is not a number", index, text),
+            InputError::Row(row, width, expected) => write!(f, "row {} is {} wide, expected {}", row, width, expected),
+            InputError::Range(text) => write!(f, "'{}' is not a range 'start-stop'", text),
+            InputError::Example(line, text) => write!(f, "line {}: '{}' is not a header '== <name> <part> <answer>'", line, text),
+            InputError::Empty => write!(f, "the input is empty"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Where a day reads its input from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source
+{
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source
+{
+    /// The input for the day in `directory`. `path` is "-" for stdin, and
+    /// relative paths are taken from the current directory, like any other
+    /// path on the command line. Without a path it is data.txt in the day's
+    /// directory, or test_data.txt when `debug` is set, so the default does
+    /// not depend on where the solution is started from.
+    pub fn new(directory: impl AsRef<Path>, path: Option<&str>, debug: bool) -> Self
+    {
+        match path
+        {
+            Some("-") => Source::Stdin,
+            Some(path) => Source::File(PathBuf::from(path)),
+            None if debug => Source::File(directory.as_ref().join(TEST_DATA_FILE_PATH)),
+            None => Source::File(directory.as_ref().join(DATA_FILE_PATH)),
+        }
+    }
+
+    pub fn read_bytes(&self) -> Result<Vec<u8>, InputError>
+    {
+        let result = match self
+        {
+            Source::File(path) => fs::read(path),
+            Source::Stdin =>
+            {
+                let mut bytes = vec![];
+                io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+            }
+        };
+
+        result.map_err(|e| InputError::Io(self.to_string(), e))
+    }
+
+    pub fn read(&self) -> Result<String, InputError>
+    {
+        String::from_utf8(self.read_bytes()?)
+            .map_err(|e| InputError::Io(self.to_string(), io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+}
+
+impl Display for Source
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// One number per line, blank lines are skipped.
+pub fn numbers<T: FromStr>(text: &str) -> Result<Vec<T>, InputError>
+{
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| line.trim().parse().map_err(|_| InputError::Number(index + 1, line.to_string())))
+        .collect()
+}
+
+/// Comma separated numbers, possibly spread over several lines.
+pub fn integers<T: FromStr>(text: &str) -> Result<Vec<T>, InputError>
+{
+    let values: Vec<T> = text
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .enumerate()
+        .map(|(index, value)| value.parse().map_err(|_| InputError::Value(index + 1, value.to_string())))
+        .collect::<Result<_, _>>()?;
+
+    if values.is_empty()
+    {
+        return Err(InputError::Empty);
+    }
+
+    Ok(values)
+}
+
+/// The characters of every non-empty line, which all have to be as wide.
+pub fn grid(text: &str) -> Result<Vec<Vec<char>>, InputError>
+{
+    let rows: Vec<Vec<char>> = text
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.chars().collect())
+        .collect();
+
+    let width = match rows.first()
+    {
+        Some(row) => row.len(),
+        None => return Err(InputError::Empty),
+    };
+    if let Some((index, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width)
+    {
+        return Err(InputError::Row(index + 1, row.len(), width));
+    }
+
+    Ok(rows)
+}
+
+/// A range written 'start-stop'.
+pub fn range<T: FromStr>(text: &str) -> Result<(T, T), InputError>
+{
+    let text = text.trim();
+    let (start, stop) = text.split_once('-').ok_or_else(|| InputError::Range(text.to_string()))?;
+
+    match (start.trim().parse(), stop.trim().parse())
+    {
+        (Ok(start), Ok(stop)) => Ok((start, stop)),
+        _ => Err(InputError::Range(text.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_helpers()
+    {
+        assert_eq!(numbers::<i32>("12\n\n14\n").unwrap(), vec![12, 14]);
+        assert!(matches!(numbers::<i32>("12\nx\n"), Err(InputError::Number(2, _))));
+        assert_eq!(integers::<i64>("1,-2,\n3\n").unwrap(), vec![1, -2, 3]);
+        assert_eq!(grid("#.\n.#\n").unwrap(), vec![vec!['#', '.'], vec!['.', '#']]);
+        assert!(matches!(grid("#.\n#\n"), Err(InputError::Row(2, 1, 2))));
+        assert_eq!(range::<u32>("10-20\n").unwrap(), (10, 20));
+        assert!(matches!(range::<u32>("10"), Err(InputError::Range(_))));
+    }
+
+    #[test]
+    fn test_source()
+    {
+        assert_eq!(Source::new("/day", Some("-"), false), Source::Stdin);
+        assert_eq!(Source::new("/day", None, true), Source::File(PathBuf::from("/day/test_data.txt")));
+        assert_eq!(Source::new("/day", Some("/tmp/input"), false), Source::File(PathBuf::from("/tmp/input")));
+        assert_eq!(Source::new("/day", Some("day/input"), true), Source::File(PathBuf::from("day/input")));
+    }
+}
@@ -0,0 +1,13 @@
+//! Code shared by every day: the `Solution` trait, running, checking and
+//! benchmarking one, and reading and parsing the input.
+
+pub mod bench;
+pub mod examples;
+pub mod input;
+mod options;
+mod solution;
+
+pub use bench::{bench, Bench, Stats};
+pub use input::{InputError, Source};
+pub use options::Options;
+pub use solution::{run, solve, Answer, Error, Solution, Timing};
@@ -0,0 +1,34 @@
+use clap::Parser;
+
+/// The options every day takes, which parts to solve and on what input.
+/// Days with options of their own flatten this into their `Args`.
+#[derive(Parser, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options
+{
+    /// Check the day's examples.txt, or use its test_data.txt when it has
+    /// none
+    #[arg(short, long, default_value_t=false)]
+    pub debug: bool,
+
+    /// Part to solve, both when left out
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    pub part: Option<u32>,
+
+    /// Read this file instead of data.txt, relative to the current
+    /// directory, or - for stdin
+    #[arg(long)]
+    pub input: Option<String>,
+}
+
+impl Options
+{
+    /// Whether each part to solve is part two, in order.
+    pub fn parts(&self) -> Vec<bool>
+    {
+        match self.part
+        {
+            Some(part) => vec![part == 2],
+            None => vec![false, true],
+        }
+    }
+}
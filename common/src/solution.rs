@@ -0,0 +1,293 @@
+use std::fmt::Display;
+use std::io;
+use std::process;
+use std::time::{Duration, Instant};
+
+use crate::examples::{self, Example};
+use crate::input::{InputError, Source};
+use crate::options::Options;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer
+{
+    Number(i64),
+    Text(String),
+}
+
+impl Display for Answer
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl From<i64> for Answer
+{
+    fn from(number: i64) -> Self
+    {
+        Answer::Number(number)
+    }
+}
+
+impl From<i32> for Answer
+{
+    fn from(number: i32) -> Self
+    {
+        Answer::Number(number as i64)
+    }
+}
+
+impl From<u32> for Answer
+{
+    fn from(number: u32) -> Self
+    {
+        Answer::Number(number as i64)
+    }
+}
+
+impl From<usize> for Answer
+{
+    fn from(number: usize) -> Self
+    {
+        Answer::Number(number as i64)
+    }
+}
+
+impl From<String> for Answer
+{
+    fn from(text: String) -> Self
+    {
+        Answer::Text(text)
+    }
+}
+
+impl Answer
+{
+    pub fn to_json(&self) -> String
+    {
+        match self
+        {
+            Answer::Number(number) => number.to_string(),
+            Answer::Text(text) =>
+            {
+                let escaped: String = text.chars().map(|c| match c
+                {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    c if c.is_control() => format!("\\u{:04x}", c as u32),
+                    c => c.to_string(),
+                }).collect();
+                format!("\"{}\"", escaped)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error
+{
+    Io(io::Error),
+    Input(InputError),
+    /// The input is not what the day expects.
+    Parse(String),
+    /// The input parsed, but solving it failed.
+    Solve(String),
+}
+
+impl Error
+{
+    pub fn solve(e: impl Display) -> Self
+    {
+        Error::Solve(e.to_string())
+    }
+}
+
+impl Display for Error
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Input(e) => write!(f, "{}", e),
+            Error::Parse(message) => write!(f, "could not parse input, {}", message),
+            Error::Solve(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error
+{
+    fn from(e: io::Error) -> Self
+    {
+        Error::Io(e)
+    }
+}
+
+impl From<InputError> for Error
+{
+    fn from(e: InputError) -> Self
+    {
+        Error::Input(e)
+    }
+}
+
+/// One day's puzzle. Parsing is separate from solving, so both parts share
+/// the parsed input and the runner can time them the same way.
+pub trait Solution
+{
+    type Input;
+
+    /// The day's directory, which its input files are relative to. Set to
+    /// `env!("CARGO_MANIFEST_DIR")`.
+    const DIRECTORY: &'static str;
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>;
+
+    /// Parses the input as read from its file. Text is all most days take,
+    /// days that also read a binary format override this.
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        let text = std::str::from_utf8(bytes).map_err(|e| Error::Parse(e.to_string()))?;
+        self.parse(text)
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Answer, Error>;
+
+    fn part_two(&self, input: &Self::Input) -> Result<Answer, Error>;
+}
+
+/// How long parsing and solving one part took.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timing
+{
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timing
+{
+    pub fn total(&self) -> Duration
+    {
+        self.parse + self.solve
+    }
+}
+
+/// Parses `input` and solves one part, returning the answer and how long
+/// parsing and solving took.
+pub fn solve<S: Solution>(solution: &S, input: impl AsRef<[u8]>, part_two: bool) -> Result<(Answer, Timing), Error>
+{
+    let start_time = Instant::now();
+    let input = solution.parse_bytes(input.as_ref())?;
+    let parse = start_time.elapsed();
+
+    let start_time = Instant::now();
+    let answer = if part_two { solution.part_two(&input)? } else { solution.part_one(&input)? };
+
+    Ok((answer, Timing { parse, solve: start_time.elapsed() }))
+}
+
+/// Solves the parts in `options` on the puzzle input, or the file given
+/// instead, printing the answers and the time they took. With `debug` set
+/// it checks the parts against the day's examples instead, or solves its
+/// test_data.txt when it has none. Exits on errors.
+pub fn run<S: Solution>(solution: &S, options: &Options)
+{
+    println!("Starting...");
+    let parts = options.parts();
+    if options.debug && options.input.is_none()
+    {
+        match examples::load(S::DIRECTORY)
+        {
+            Ok(Some(examples)) =>
+            {
+                run_examples(solution, examples.into_iter().filter(|example| parts.contains(&(example.part == 2))).collect());
+                return;
+            }
+            Ok(None) => (),
+            Err(e) =>
+            {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let source = Source::new(S::DIRECTORY, options.input.as_deref(), options.debug);
+    let bytes = match source.read_bytes()
+    {
+        Ok(bytes) => bytes,
+        Err(e) =>
+        {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut duration = Duration::ZERO;
+    for part_two in parts.iter().copied()
+    {
+        match solve(solution, &bytes, part_two)
+        {
+            Ok((answer, timing)) =>
+            {
+                let label = match parts.len()
+                {
+                    1 => "The result is:".to_string(),
+                    _ => format!("The result of part {} is:", if part_two { 2 } else { 1 }),
+                };
+                // Answers drawn as text start on a line of their own
+                match answer.to_string()
+                {
+                    text if text.contains('\n') => println!("{}\n{}", label, text.trim_end()),
+                    text => println!("{} {}", label, text),
+                }
+                duration += timing.total();
+            }
+            Err(e) =>
+            {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+    println!("Finished running in: {:.3?}", duration);
+}
+
+/// Prints whether each example passed, and exits with 1 if any failed.
+fn run_examples<S: Solution>(solution: &S, examples: Vec<Example>)
+{
+    let start_time = Instant::now();
+
+    let count = examples.len();
+    if count == 0
+    {
+        println!("There are no examples for this part");
+        return;
+    }
+
+    let mut passed = 0;
+    for outcome in examples::check(solution, examples)
+    {
+        println!("{}", outcome);
+        if outcome.passed()
+        {
+            passed += 1;
+        }
+    }
+
+    println!("{} of {} examples pass", passed, count);
+    println!("Finished running in: {:.3?}", start_time.elapsed());
+    if passed < count
+    {
+        process::exit(1);
+    }
+}
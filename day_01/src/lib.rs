@@ -0,0 +1,49 @@
+use aoc_common::{input, Answer, Error, Solution};
+
+fn process_number(num: i32) -> i32
+{
+    let decimal: f32 = num as f32;
+
+    let decimal = decimal / 3.0;
+
+    let rounded: i32 = decimal as i32;
+
+    rounded - 2
+}
+
+fn process_number_recursive(num: i32) -> i32
+{
+    let fuel: i32 = process_number(num);
+
+    if fuel > 0
+    {
+        let temp = fuel + process_number_recursive(fuel);
+        //println!("Temp: {}", temp);
+        return temp;
+    }
+
+    if fuel > 0 { fuel } else { 0 }
+}
+
+pub struct Day01;
+
+impl Solution for Day01
+{
+    type Input = Vec<i32>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        Ok(input::numbers(text)?)
+    }
+
+    fn part_one(&self, numbers: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(numbers.iter().map(|&num| process_number(num)).sum::<i32>().into())
+    }
+
+    fn part_two(&self, numbers: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(numbers.iter().map(|&num| process_number_recursive(num)).sum::<i32>().into())
+    }
+}
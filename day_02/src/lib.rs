@@ -0,0 +1,250 @@
+use std::process;
+
+use aoc_common::examples;
+use aoc_common::{Answer, Error, Options, Solution, Source};
+use clap::Parser;
+use intcode::diff::diff;
+use intcode::Vm;
+
+const MAGIC_NUMBER: i64 = 19690720;
+const MAGIC_NUMBER_DEBUG: i64 = 30;
+
+/// The program with `noun` and `verb` at addresses 1 and 2.
+fn with_inputs(program: &[i64], noun: i64, verb: i64) -> Result<Vec<i64>, Error>
+{
+    if program.len() < 3
+    {
+        return Err(Error::Parse(format!("the program has {} cell(s), the noun and verb go at 1 and 2", program.len())));
+    }
+
+    let mut program = program.to_vec();
+    program[1] = noun;
+    program[2] = verb;
+
+    Ok(program)
+}
+
+fn run_program(program: &mut Vec<i64>) -> Result<(), Error>
+{
+    intcode::run_program(program, 0).map_err(|e| Error::Solve(format!("program failed, {}", e)))?;
+
+    Ok(())
+}
+
+/// Runs the program recorded and prints every cell it changed compared to
+/// `image`, and which instruction wrote it.
+fn print_memory_diff(image: &[i64], program: &[i64]) -> Result<(), Error>
+{
+    let mut vm = Vm::new(program.to_vec());
+    vm.record();
+    if let Err(e) = vm.run()
+    {
+        return Err(Error::Solve(format!("program failed at {}, {}", vm.pc, e)));
+    }
+
+    println!("Memory changed by the run:");
+    for range in diff(image, &vm.memory, vm.log())
+    {
+        print!("{}", range);
+    }
+
+    Ok(())
+}
+
+/// Searches the bounds for the noun and verb that give `magic_number`,
+/// printing every guess when `debug` is set.
+fn solve_for_values(program: &[i64], magic_number: i64, noun_bound: (i64, i64), verb_bound: (i64, i64), debug: bool) -> Result<(i64, i64), Error>
+{
+    let noun: i64 = (noun_bound.1 + noun_bound.0) / 2; 
+    let verb: i64 = (verb_bound.1 + verb_bound.0) / 2; 
+
+    let mut new_program = with_inputs(program, noun, verb)?;
+    run_program(&mut new_program)?;
+
+    let solution: i64 = new_program[0];
+    if debug
+    {
+        println!("Noun: {} ({}, {}), verb: {} ({}, {}), solution: {} (MN: {})", noun, noun_bound.0, noun_bound.1, verb, verb_bound.0, verb_bound.1, solution, magic_number);
+    }
+
+    if solution == magic_number
+    {
+        Ok((noun, verb))
+    }
+    else if solution < magic_number
+    {
+        if noun < noun_bound.1
+        {
+            let sub = solve_for_values(program, magic_number, (noun + 1, noun_bound.1), verb_bound, debug)?;
+            if sub != (0, 0)
+            {
+                return Ok(sub);
+            }
+        }
+        if verb < verb_bound.1
+        {
+            let sub = solve_for_values(program, magic_number, noun_bound, (verb + 1, verb_bound.1), debug)?;
+
+            if sub != (0, 0)
+            {
+                return Ok(sub);
+            }
+        }
+        Ok((0, 0))
+    }
+    else // solution > magic_number
+    {
+        if noun == 0
+        {
+            return Ok((0, 0));
+        }
+        if noun_bound.0 < noun
+        {
+            let sub = solve_for_values(program, magic_number, (noun_bound.0, noun - 1), verb_bound, debug)?;
+            if sub != (0, 0)
+            {
+                return Ok(sub);
+            }
+        }
+        if verb == 0
+        {
+            return Ok((0, 0));
+        }
+        if verb_bound.0 < verb
+        {
+            let sub = solve_for_values(program, magic_number, noun_bound, (verb_bound.0, verb - 1), debug)?;
+            if sub != (0, 0)
+            {
+                return Ok(sub);
+            }
+        }
+        
+        Ok((0, 0))
+    }
+}
+
+fn solve_for_magic_number(program: &[i64], magic_number: i64, debug: bool) -> Result<i64, Error>
+{
+    let mut noun_bound: (i64, i64) = (0, 99);
+    let mut verb_bound: (i64, i64) = (0, 99);
+    if debug && noun_bound.1 > program.len() as i64
+    {
+        noun_bound.1 = program.len() as i64;
+        verb_bound.1 = program.len() as i64;
+    }
+    let (noun, verb) = solve_for_values(program, magic_number, noun_bound, verb_bound, debug)?;
+
+    Ok(100 * noun + verb)
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    pub options: Options,
+
+    /// Value position 0 has to end up with in part two, defaults to
+    /// 19690720, or 30 for the examples
+    #[arg(short, long)]
+    pub magic_number: Option<i64>,
+
+    /// Print every memory cell the part one run changes, and which
+    /// instruction wrote it
+    #[arg(long, default_value_t=false)]
+    pub diff: bool,
+}
+
+/// The example programs from the puzzle text are run as they are, and
+/// searched for a different output, so `example` is set when running them.
+pub struct Day02
+{
+    pub example: bool,
+    pub magic_number: Option<i64>,
+}
+
+impl Day02
+{
+    /// The image as part one runs it, set to the 1202 program alarm state
+    /// unless it is an example.
+    fn part_one_program(&self, image: &[i64]) -> Result<Vec<i64>, Error>
+    {
+        if self.example
+        {
+            Ok(image.to_vec())
+        }
+        else
+        {
+            with_inputs(image, 12, 2)
+        }
+    }
+}
+
+impl Solution for Day02
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        intcode::parse_program(text).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    fn part_one(&self, image: &Self::Input) -> Result<Answer, Error>
+    {
+        let mut program = self.part_one_program(image)?;
+        run_program(&mut program)?;
+
+        Ok(program[0].into())
+    }
+
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        let magic_number = match self.magic_number
+        {
+            Some(magic_number) => magic_number,
+            None if self.example => MAGIC_NUMBER_DEBUG,
+            None => MAGIC_NUMBER,
+        };
+
+        Ok(solve_for_magic_number(program, magic_number, self.example)?.into())
+    }
+}
+
+/// Prints the memory diff of part one, for data.txt or the file given
+/// with `--input`, or with `--debug` for every part one example.
+fn print_part_one_diff(day: &Day02, options: &Options) -> Result<(), Error>
+{
+    // With --debug part one runs the examples, so those are what change
+    if options.debug
+        && options.input.is_none()
+        && let Some(examples) = examples::load(Day02::DIRECTORY)?
+    {
+        for example in examples.iter().filter(|example| example.part == 1)
+        {
+            println!("Example {}:", example.name);
+            let image = day.parse(&example.input)?;
+            print_memory_diff(&image, &day.part_one_program(&image)?)?;
+        }
+
+        return Ok(());
+    }
+
+    let source = Source::new(Day02::DIRECTORY, options.input.as_deref(), options.debug);
+    let image = day.parse(&source.read()?)?;
+
+    print_memory_diff(&image, &day.part_one_program(&image)?)
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    let day = Day02 { example: args.options.debug, magic_number: args.magic_number };
+    if args.diff
+        && let Err(e) = print_part_one_diff(&day, &args.options)
+    {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+    aoc_common::run(&day, &args.options);
+}
@@ -0,0 +1,118 @@
+use aoc_common::{input, Answer, Error, Solution};
+
+fn check_criteria(number: u32) -> bool
+{
+    let mut previous_digit: i16 = -1;
+    let mut adjacent_equal = false;
+    for digit_char in number.to_string().chars()
+    {
+        let digit = u32::from(digit_char) as i16;
+        if digit < previous_digit
+        {
+            return false;
+        }
+        if previous_digit == digit
+        {
+            adjacent_equal = true;
+        }
+        previous_digit = digit;
+    }
+    
+    adjacent_equal
+}
+
+pub fn solve_1(start: u32, stop: u32) -> u32
+{
+    let mut counter = 0;
+
+    for num in start..=stop
+    {
+        if check_criteria(num)
+        {
+            counter += 1;
+        }
+    }
+
+    counter
+}
+
+pub fn extra_criertion(number: u32) -> bool
+{
+    let mut previous = 'a';
+    let mut current_count = 0;
+
+    for digit in number.to_string().chars()
+    {
+        if digit == previous
+        {
+            current_count += 1;
+        }
+        else
+        {
+            if current_count == 2
+            {
+                return true;
+            }
+
+            previous = digit;
+            current_count = 1;
+        }
+    }
+
+    current_count == 2
+}
+
+pub fn solve_2(start: u32, stop: u32) -> u32
+{
+    let mut counter = 0;
+
+    for num in start..=stop
+    {
+        if check_criteria(num) && extra_criertion(num)
+        {
+            counter += 1;
+        }
+    }
+
+    counter
+}
+
+pub struct Day04;
+
+impl Solution for Day04
+{
+    /// The range of passwords, both ends included.
+    type Input = (u32, u32);
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        match input::range(text)?
+        {
+            (0, _) | (_, 0) => Err(Error::Parse("we expect both start and stop to differ from 0 (zero), please check your file".to_string())),
+            range => Ok(range),
+        }
+    }
+
+    fn part_one(&self, &(start_number, stop_number): &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(solve_1(start_number, stop_number).into())
+    }
+
+    fn part_two(&self, &(start_number, stop_number): &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(solve_2(start_number, stop_number).into())
+    }
+}
+
+#[cfg(test)] // Tells Rust to only compile this module when running cargo test
+mod tests {
+    use super::*; // Imports functions from the outer module
+
+    #[test] // Marks this function as a test
+    fn test_extra_crietrion() {                
+        assert!(extra_criertion(112233));
+        assert!(!extra_criertion(123444));
+        assert!(extra_criertion(111122));
+    }
+}
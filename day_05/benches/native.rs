@@ -0,0 +1,54 @@
+//! Compares the interpreter against the programs compiled by `build.rs`.
+//! Run with `cargo bench`.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+mod native
+{
+    include!(concat!(env!("OUT_DIR"), "/data.rs"));
+    include!(concat!(env!("OUT_DIR"), "/test_data.rs"));
+}
+
+const RUNS: u32 = 1000;
+
+type Native = fn(&mut Vec<i64>, &[i64]) -> Result<Vec<i64>, intcode::Error>;
+
+fn mean<F: FnMut()>(mut f: F) -> Duration
+{
+    let start_time = Instant::now();
+    for _ in 0..RUNS
+    {
+        f();
+    }
+
+    start_time.elapsed() / RUNS
+}
+
+fn compare(filename: &str, compiled: Native, input: i64)
+{
+    let text = fs::read_to_string(filename).expect("could not read program");
+    let program = intcode::parse_program(&text).expect("could not parse program");
+
+    let expected = intcode::run_program(&mut program.clone(), input).expect("interpreter failed");
+    let actual = compiled(&mut program.clone(), &[input]).expect("compiled program failed");
+    assert_eq!(expected, actual, "{}: compiled program disagrees with the interpreter", filename);
+
+    let interpreted = mean(|| { intcode::run_program(&mut program.clone(), input).unwrap(); });
+    let native = mean(|| { compiled(&mut program.clone(), &[input]).unwrap(); });
+
+    println!("{:<14} input {:<2} interpreter: {:>10.3?}  native: {:>10.3?}  ({:.1}x)",
+        filename, input, interpreted, native, interpreted.as_secs_f64() / native.as_secs_f64());
+}
+
+fn main()
+{
+    for input in [7, 8, 9]
+    {
+        compare("test_data.txt", native::test_data, input);
+    }
+    for input in [1, 5]
+    {
+        compare("data.txt", native::data, input);
+    }
+}
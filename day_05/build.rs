@@ -0,0 +1,10 @@
+fn main()
+{
+    for (source, name) in [("data.txt", "data"), ("test_data.txt", "test_data")]
+    {
+        if let Err(e) = intcode::transpile::build(source, name)
+        {
+            panic!("Error: could not transpile {}, {}", source, e);
+        }
+    }
+}
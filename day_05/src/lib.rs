@@ -0,0 +1,216 @@
+use std::fs;
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+use intcode::optimize::{equivalent, optimize};
+use intcode::record::Log;
+use intcode::Vm;
+
+mod report;
+use report::Report;
+
+const AIR_CONDITIONER_ID: i64 = 1;
+const THERMAL_RADIATOR_ID: i64 = 5;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// System ID(s) to feed the program, comma separated. Defaults to 1 for
+    /// part one and 5 for part two. Used to be --input, which now names
+    /// the input file
+    #[arg(short, long, value_delimiter = ',', allow_negative_numbers = true)]
+    system_id: Option<Vec<i64>>,
+
+    /// Run the program compiled to Rust at build time instead of interpreting it
+    #[arg(short, long, default_value_t=false)]
+    native: bool,
+
+    /// Run the program through the peephole optimizer first
+    #[arg(short, long, default_value_t=false)]
+    optimize: bool,
+
+    /// Print the diagnostic report of every run, tracing failed tests back
+    /// to the instruction that computed them
+    #[arg(long, default_value_t=false)]
+    report: bool,
+
+    /// Print the diagnostic report as JSON instead of a table
+    #[arg(short, long, default_value_t=false)]
+    json: bool,
+
+    /// Record the run and print every write to this address
+    #[arg(long)]
+    history: Option<usize>,
+
+    /// Record the run and save the execution log to this file
+    #[arg(long)]
+    record: Option<String>,
+}
+
+mod native
+{
+    include!(concat!(env!("OUT_DIR"), "/data.rs"));
+    include!(concat!(env!("OUT_DIR"), "/test_data.rs"));
+}
+
+/// The diagnostic program. The fields are the options of the same name,
+/// and `debug` picks the native program compiled from test_data.txt over
+/// the one compiled from data.txt.
+#[derive(Default)]
+pub struct Day05
+{
+    pub debug: bool,
+    pub system_id: Option<Vec<i64>>,
+    pub native: bool,
+    pub optimize: bool,
+    pub report: bool,
+    pub json: bool,
+    pub history: Option<usize>,
+    pub record: Option<String>,
+}
+
+impl Day05
+{
+    fn run_diagnostic(&self, program: &mut Vec<i64>, inputs: &[i64]) -> Result<Report, intcode::Error>
+    {
+        let outputs = match (self.native, self.debug)
+        {
+            // Only a report that is printed needs the culprits
+            (false, _) if self.report || self.json => return Report::trace(Vm::new(program.clone()), inputs),
+            (false, _) => intcode::run_with_inputs(program, inputs),
+            (true, false) => native::data(program, inputs),
+            (true, true) => native::test_data(program, inputs),
+        };
+
+        outputs.map(|outputs| Report::from_outputs(&outputs))
+    }
+
+    /// Runs the diagnostic program for one system, or the ones given with
+    /// `system_id`, failing if any of its tests do.
+    fn diagnostic_code(&self, program: &[i64], system_id: i64) -> Result<Answer, Error>
+    {
+        let inputs = self.system_id.clone().unwrap_or_else(|| vec![system_id]);
+        let mut program = program.to_vec();
+
+        if self.optimize
+        {
+            let (optimized, report) = optimize(&program);
+            print!("{}", report);
+            match equivalent(&program, &optimized, &report, &inputs)
+            {
+                Ok(true) => program = optimized,
+                Ok(false) => return Err(Error::Solve("optimized program does not behave like the original".to_string())),
+                Err(e) => return Err(Error::Solve(format!("program failed, {}", e))),
+            }
+        }
+
+        if self.history.is_some() || self.record.is_some()
+        {
+            let log = record_run(&program, &inputs)?;
+            if let Some(address) = self.history
+            {
+                print_history(&log, address);
+            }
+            if let Some(path) = &self.record
+            {
+                fs::write(path, log.to_string()).map_err(|e| Error::Solve(format!("could not write log to {}, {}", path, e)))?;
+            }
+        }
+
+        let report = self.run_diagnostic(&mut program, &inputs).map_err(|e| Error::Solve(format!("program failed, {}", e)))?;
+        if self.json
+        {
+            println!("{}", report.to_json());
+        }
+        else if self.report
+        {
+            report.print_table();
+        }
+
+        let failed = report.tests.iter().filter(|test| !test.passed()).count();
+        if failed > 0
+        {
+            return Err(Error::Solve(format!("{} diagnostic test(s) failed", failed)));
+        }
+
+        match report.code
+        {
+            Some(code) => Ok(code.value.into()),
+            None => Err(Error::Solve("the program produced no output".to_string())),
+        }
+    }
+}
+
+impl Solution for Day05
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        self.diagnostic_code(program, AIR_CONDITIONER_ID)
+    }
+
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        self.diagnostic_code(program, THERMAL_RADIATOR_ID)
+    }
+}
+
+fn record_run(program: &[i64], inputs: &[i64]) -> Result<Log, Error>
+{
+    let mut vm = Vm::new(program.to_vec());
+    vm.record();
+    for &input in inputs
+    {
+        vm.push_input(input);
+    }
+    if let Err(e) = vm.run()
+    {
+        return Err(Error::Solve(format!("program failed at {}, {}", vm.pc, e)));
+    }
+
+    Ok(vm.take_log().unwrap_or_default())
+}
+
+fn print_history(log: &Log, address: usize)
+{
+    for (index, step) in log.history(address)
+    {
+        if let Some(write) = step.write
+        {
+            println!("step {:>5} pc {:>4}: [{}] {} => {}", index, step.pc, address, write.old, write.new);
+        }
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    let day = Day05
+    {
+        debug: args.options.debug,
+        system_id: args.system_id,
+        native: args.native,
+        optimize: args.optimize,
+        report: args.report,
+        json: args.json,
+        history: args.history,
+        record: args.record,
+    };
+    aoc_common::run(&day, &args.options);
+}
@@ -0,0 +1,272 @@
+use intcode::record::Step;
+use intcode::{Error, Mode, Opcode, State, Vm};
+
+/// The instruction that last wrote the cell an OUTPUT printed.
+pub struct Culprit
+{
+    pub address: usize,
+    /// Name of the opcode, as the machine's table calls it.
+    pub opcode: String,
+    pub step: usize,
+    pub cell: usize,
+    pub value: i64,
+}
+
+pub struct TestResult
+{
+    pub index: usize,
+    /// Address of the OUTPUT instruction, unknown for native runs.
+    pub address: Option<usize>,
+    pub value: i64,
+    pub culprit: Option<Culprit>,
+}
+
+impl TestResult
+{
+    pub fn passed(&self) -> bool
+    {
+        self.value == 0
+    }
+}
+
+/// Every output but the last is a diagnostic test, zero meaning it passed.
+/// The last one is the diagnostic code.
+pub struct Report
+{
+    pub tests: Vec<TestResult>,
+    pub code: Option<TestResult>,
+}
+
+impl Report
+{
+    /// Builds a report from bare outputs, without addresses or culprits.
+    pub fn from_outputs(outputs: &[i64]) -> Self
+    {
+        let results = outputs
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| TestResult { index, address: None, value, culprit: None })
+            .collect();
+
+        Report::new(results)
+    }
+
+    /// Runs the machine's program recorded and traces every output back to
+    /// the instruction that computed it. Instructions are decoded with the
+    /// machine's own opcodes, so custom ones are traced too.
+    pub fn trace(mut vm: Vm, inputs: &[i64]) -> Result<Self, Error>
+    {
+        vm.record();
+        for &input in inputs
+        {
+            vm.push_input(input);
+        }
+
+        // Cell printed at each step, None unless it is an OUTPUT reading
+        // memory, as custom opcodes print values their handler computed
+        let mut sources: Vec<Option<usize>> = vec![];
+        // Name of the opcode run at each step
+        let mut opcodes: Vec<String> = vec![];
+        loop
+        {
+            let instruction = vm.instruction_at(vm.pc)?;
+            sources.push(match (instruction.opcode, instruction.first_param)
+            {
+                (Opcode::OUTPUT, Mode::POSITION) => usize::try_from(vm.read(vm.pc + 1)).ok(),
+                (Opcode::OUTPUT, Mode::RELATIVE) => usize::try_from(vm.relative_base + vm.read(vm.pc + 1)).ok(),
+                _ => None,
+            });
+            opcodes.push(match vm.table()
+            {
+                Some(table) => table.name(instruction.opcode),
+                None => instruction.opcode.to_string(),
+            });
+
+            match vm.step()?
+            {
+                None => {}
+                Some(State::Halted) => break,
+                Some(State::NeedInput) => return Err(Error::InputExhausted),
+            }
+        }
+
+        let steps: &[Step] = vm.log().map(|log| &log.steps[..]).unwrap_or(&[]);
+        let results = steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| step.output.is_some())
+            .enumerate()
+            .map(|(index, (step_index, step))|
+            {
+                let culprit = sources[step_index].and_then(|cell| find_culprit(steps, &opcodes, step_index, cell));
+                TestResult { index, address: Some(step.pc), value: step.output.unwrap_or(0), culprit }
+            })
+            .collect();
+
+        Ok(Report::new(results))
+    }
+
+    fn new(mut results: Vec<TestResult>) -> Self
+    {
+        let code = results.pop();
+        Report { tests: results, code }
+    }
+
+    pub fn passed(&self) -> bool
+    {
+        self.tests.iter().all(TestResult::passed)
+    }
+
+    pub fn print_table(&self)
+    {
+        println!("{:>4} | {:>7} | {:>10} | {:<6} | Computed by", "Test", "Address", "Value", "Result");
+        for test in &self.tests
+        {
+            let culprit = match (&test.culprit, test.passed())
+            {
+                (Some(c), false) => format!("{} at {} (step {}) wrote {} to [{}]", c.opcode, c.address, c.step, c.value, c.cell),
+                _ => String::new(),
+            };
+            println!("{:>4} | {:>7} | {:>10} | {:<6} | {}",
+                test.index, optional(test.address), test.value, if test.passed() { "pass" } else { "FAIL" }, culprit);
+        }
+        match &self.code
+        {
+            Some(code) => println!("Diagnostic code: {} (output at {})", code.value, optional(code.address)),
+            None => println!("The program produced no output"),
+        }
+    }
+
+    pub fn to_json(&self) -> String
+    {
+        let tests: Vec<String> = self.tests.iter().map(|test| test_json(test, true)).collect();
+        let code = match &self.code
+        {
+            Some(code) => test_json(code, false),
+            None => "null".to_string(),
+        };
+
+        format!("{{\"passed\":{},\"tests\":[{}],\"code\":{}}}", self.passed(), tests.join(","), code)
+    }
+}
+
+fn optional(value: Option<usize>) -> String
+{
+    value.map_or("-".to_string(), |value| value.to_string())
+}
+
+fn json_optional(value: Option<usize>) -> String
+{
+    value.map_or("null".to_string(), |value| value.to_string())
+}
+
+fn test_json(test: &TestResult, is_test: bool) -> String
+{
+    let mut json = format!("{{\"index\":{},\"address\":{},\"value\":{}", test.index, json_optional(test.address), test.value);
+    if is_test
+    {
+        json.push_str(&format!(",\"passed\":{}", test.passed()));
+    }
+    if let Some(c) = &test.culprit
+    {
+        json.push_str(&format!(",\"computed_by\":{{\"address\":{},\"opcode\":\"{}\",\"step\":{},\"cell\":{},\"value\":{}}}",
+            c.address, c.opcode, c.step, c.cell, c.value));
+    }
+    json.push('}');
+
+    json
+}
+
+/// Last step before `before` that wrote `cell`.
+fn find_culprit(steps: &[Step], opcodes: &[String], before: usize, cell: usize) -> Option<Culprit>
+{
+    steps[..before]
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, step)|
+        {
+            let write = step.write.filter(|write| write.address == cell)?;
+            Some(Culprit { address: step.pc, opcode: opcodes[index].clone(), step: index, cell, value: write.new })
+        })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use intcode::instruction::Param;
+    use intcode::table::{Effect, OpcodeTable};
+    use std::rc::Rc;
+
+    /// Reads the system ID into [20], writes `first + second` to [21] and
+    /// outputs [21] as the one test and [20] as the diagnostic code.
+    fn diagnostic(first: i64, second: i64) -> Vec<i64>
+    {
+        let mut program = vec![3, 20, 1101, first, second, 21, 4, 21, 4, 20, 99];
+        program.resize(22, 0);
+
+        program
+    }
+
+    #[test]
+    fn test_trace()
+    {
+        let report = Report::trace(Vm::new(diagnostic(0, 0)), &[7]).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.tests.len(), 1);
+        assert_eq!(report.tests[0].address, Some(6));
+        assert_eq!(report.to_json(), concat!(
+            "{\"passed\":true,\"tests\":[{\"index\":0,\"address\":6,\"value\":0,\"passed\":true,",
+            "\"computed_by\":{\"address\":2,\"opcode\":\"ADD\",\"step\":1,\"cell\":21,\"value\":0}}],",
+            "\"code\":{\"index\":1,\"address\":8,\"value\":7,",
+            "\"computed_by\":{\"address\":0,\"opcode\":\"INPUT\",\"step\":0,\"cell\":20,\"value\":7}}}"));
+
+        let report = Report::trace(Vm::new(diagnostic(2, 3)), &[7]).unwrap();
+        assert!(!report.passed());
+        let culprit = report.tests[0].culprit.as_ref().unwrap();
+        assert_eq!((culprit.address, culprit.opcode.as_str(), culprit.step, culprit.cell, culprit.value), (2, "ADD", 1, 21, 5));
+        assert!(report.to_json().starts_with("{\"passed\":false,\"tests\":[{\"index\":0,\"address\":6,\"value\":5,\"passed\":false,"));
+
+        assert!(matches!(Report::trace(Vm::new(diagnostic(0, 0)), &[]), Err(Error::InputExhausted)));
+    }
+
+    #[test]
+    fn test_trace_custom_opcode()
+    {
+        let mut table = OpcodeTable::new();
+        table.register(20, "DOUBLE", &[Param::Read, Param::Write], |args| Ok(Effect::write(args[0] * 2))).unwrap();
+
+        // Doubles the system ID into [21] instead of adding
+        let mut program = vec![3, 20, 20, 20, 21, 4, 21, 4, 20, 99];
+        program.resize(22, 0);
+
+        let report = Report::trace(Vm::with_table(program.clone(), Rc::new(table)), &[0]).unwrap();
+        assert!(report.passed());
+        let culprit = report.tests[0].culprit.as_ref().unwrap();
+        assert_eq!((culprit.address, culprit.opcode.as_str()), (2, "DOUBLE"));
+        assert!(matches!(Report::trace(Vm::new(program), &[0]), Err(Error::InvalidOpcode(20))));
+    }
+
+    #[test]
+    fn test_trace_custom_output()
+    {
+        let mut table = OpcodeTable::new();
+        table.register(30, "PASS", &[], |_| Ok(Effect::output(0))).unwrap();
+        table.register(31, "HALT_WITH", &[Param::Read], |args| Ok(Effect::halt_with(args[0]))).unwrap();
+
+        // A custom test before a traced one, then the system ID as the
+        // diagnostic code from a custom halt
+        let mut program = vec![3, 20, 30, 1101, 2, 3, 21, 4, 21, 31, 20];
+        program.resize(22, 0);
+
+        let report = Report::trace(Vm::with_table(program, Rc::new(table)), &[7]).unwrap();
+        assert_eq!(report.tests.len(), 2);
+        assert!(report.tests[0].passed());
+        assert!(report.tests[0].culprit.is_none());
+        let culprit = report.tests[1].culprit.as_ref().unwrap();
+        assert_eq!((report.tests[1].value, culprit.address, culprit.opcode.as_str()), (5, 3, "ADD"));
+        let code = report.code.as_ref().unwrap();
+        assert_eq!((code.address, code.value), (Some(9), 7));
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colour
+{
+    BLACK = 0,
+    WHITE = 1,
+}
+
+impl Colour
+{
+    pub fn new(value: i64) -> Option<Self>
+    {
+        match value
+        {
+            0 => Some(Colour::BLACK),
+            1 => Some(Colour::WHITE),
+            _ => None,
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction
+{
+    UP,
+    RIGHT,
+    DOWN,
+    LEFT,
+}
+
+impl Direction
+{
+    pub fn turn_left(self) -> Self
+    {
+        match self
+        {
+            Direction::UP => Direction::LEFT,
+            Direction::LEFT => Direction::DOWN,
+            Direction::DOWN => Direction::RIGHT,
+            Direction::RIGHT => Direction::UP,
+        }
+    }
+
+    pub fn turn_right(self) -> Self
+    {
+        match self
+        {
+            Direction::UP => Direction::RIGHT,
+            Direction::RIGHT => Direction::DOWN,
+            Direction::DOWN => Direction::LEFT,
+            Direction::LEFT => Direction::UP,
+        }
+    }
+
+    /// The panel next to `(x, y)` in this direction, y growing downwards.
+    pub fn step(self, (x, y): (i64, i64)) -> (i64, i64)
+    {
+        match self
+        {
+            Direction::UP => (x, y - 1),
+            Direction::RIGHT => (x + 1, y),
+            Direction::DOWN => (x, y + 1),
+            Direction::LEFT => (x - 1, y),
+        }
+    }
+}
+
+/// The panels the robot has painted, every other panel is black.
+#[derive(Debug, Default)]
+pub struct Hull
+{
+    panels: HashMap<(i64, i64), Colour>,
+}
+
+impl Hull
+{
+    pub fn colour(&self, position: (i64, i64)) -> Colour
+    {
+        self.panels.get(&position).copied().unwrap_or(Colour::BLACK)
+    }
+
+    pub fn paint(&mut self, position: (i64, i64), colour: Colour)
+    {
+        self.panels.insert(position, colour);
+    }
+
+    /// Number of panels painted at least once, whatever colour they ended up.
+    pub fn painted(&self) -> usize
+    {
+        self.panels.len()
+    }
+
+    /// Smallest and largest corner of the white panels, None if there are
+    /// none.
+    fn bounds(&self) -> Option<((i64, i64), (i64, i64))>
+    {
+        let white = self.panels.iter().filter(|(_, colour)| **colour == Colour::WHITE).map(|(position, _)| position);
+
+        white.fold(None, |bounds, &(x, y)| match bounds
+        {
+            None => Some(((x, y), (x, y))),
+            Some(((min_x, min_y), (max_x, max_y))) => Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))),
+        })
+    }
+
+    /// Rows of the white panels' bounding box, '#' for white and '.' for black.
+    pub fn render(&self) -> String
+    {
+        let ((min_x, min_y), (max_x, max_y)) = match self.bounds()
+        {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+
+        let mut text = String::new();
+        for y in min_y..=max_y
+        {
+            for x in min_x..=max_x
+            {
+                text.push(if self.colour((x, y)) == Colour::WHITE { '#' } else { '.' });
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// Writes the rendering as a binary PPM image, every panel `scale`
+    /// pixels wide, with a one panel black border.
+    pub fn write_ppm(&self, path: impl AsRef<Path>, scale: usize) -> io::Result<()>
+    {
+        let text = self.render();
+        let rows: Vec<&str> = text.lines().collect();
+        let columns = rows.first().map_or(0, |row| row.len());
+        let width = (columns + 2) * scale;
+        let height = (rows.len() + 2) * scale;
+
+        let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for y in 0..height
+        {
+            for x in 0..width
+            {
+                let white = (y / scale).checked_sub(1)
+                    .zip((x / scale).checked_sub(1))
+                    .and_then(|(row, column)| rows.get(row).and_then(|row| row.as_bytes().get(column)))
+                    == Some(&b'#');
+                let value = if white { 255 } else { 0 };
+                image.extend_from_slice(&[value, value, value]);
+            }
+        }
+
+        fs::write(path, image)
+    }
+}
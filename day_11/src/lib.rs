@@ -0,0 +1,188 @@
+use std::fmt::Display;
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+use intcode::{State, Vm};
+
+mod hull;
+use hull::{Colour, Direction, Hull};
+
+/// Pixels to a panel in the saved image, unless told otherwise.
+const SCALE: usize = 8;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// Also save the painted hull as a PPM image to this file
+    #[arg(short, long)]
+    image: Option<String>,
+
+    /// Size in pixels of one panel in the image
+    #[arg(short, long, default_value_t=SCALE)]
+    scale: usize,
+}
+
+#[derive(Debug)]
+enum PaintError
+{
+    Program(intcode::Error),
+    InvalidColour(i64),
+    InvalidTurn(i64),
+    /// The program halted after a colour without saying where to turn.
+    UnpairedColour(i64),
+}
+
+impl Display for PaintError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            PaintError::Program(e) => write!(f, "{}", e),
+            PaintError::InvalidColour(value) => write!(f, "robot asked for colour {}", value),
+            PaintError::InvalidTurn(value) => write!(f, "robot asked to turn {}", value),
+            PaintError::UnpairedColour(value) => write!(f, "robot halted after colour {} without a turn", value),
+        }
+    }
+}
+
+impl From<intcode::Error> for PaintError
+{
+    fn from(e: intcode::Error) -> Self
+    {
+        PaintError::Program(e)
+    }
+}
+
+/// Runs the robot's program on a hull where only the starting panel has
+/// been painted, `start`. The program reads the colour under the robot and
+/// answers with the colour to paint it and which way to turn, 0 for left
+/// and 1 for right, after which the robot moves one panel forward.
+fn paint(program: Vec<i64>, start: Colour) -> Result<Hull, PaintError>
+{
+    let mut hull = Hull::default();
+    let mut position = (0, 0);
+    let mut direction = Direction::UP;
+    if start == Colour::WHITE
+    {
+        hull.paint(position, start);
+    }
+
+    let mut vm = Vm::new(program);
+    let mut pending: Vec<i64> = vec![];
+    loop
+    {
+        let state = vm.run()?;
+        pending.extend(vm.take_output());
+
+        // Outputs come in pairs, a run may stop between the two
+        let complete = pending.len() - pending.len() % 2;
+        for pair in pending.drain(..complete).collect::<Vec<_>>().chunks(2)
+        {
+            let colour = Colour::new(pair[0]).ok_or(PaintError::InvalidColour(pair[0]))?;
+            hull.paint(position, colour);
+
+            direction = match pair[1]
+            {
+                0 => direction.turn_left(),
+                1 => direction.turn_right(),
+                other => return Err(PaintError::InvalidTurn(other)),
+            };
+            position = direction.step(position);
+        }
+
+        match (state, pending.first())
+        {
+            (State::Halted, Some(&colour)) => return Err(PaintError::UnpairedColour(colour)),
+            (State::Halted, None) => break,
+            (State::NeedInput, _) => vm.push_input(hull.colour(position) as i64),
+        }
+    }
+
+    Ok(hull)
+}
+
+/// The hull painting robot. `image` is where to save the painted hull as
+/// a PPM image as well, with `scale` pixels to a panel.
+pub struct Day11
+{
+    pub image: Option<String>,
+    pub scale: usize,
+}
+
+impl Default for Day11
+{
+    fn default() -> Self
+    {
+        Day11 { image: None, scale: SCALE }
+    }
+}
+
+impl Day11
+{
+    fn paint(&self, program: &[i64], start: Colour) -> Result<Hull, Error>
+    {
+        let hull = paint(program.to_vec(), start).map_err(|e| Error::Solve(format!("robot failed, {}", e)))?;
+        if let Some(path) = &self.image
+        {
+            hull.write_ppm(path, self.scale).map_err(|e| Error::Solve(format!("could not write image to {}, {}", path, e)))?;
+        }
+
+        Ok(hull)
+    }
+}
+
+impl Solution for Day11
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// The number of panels painted from a black start.
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(self.paint(program, Colour::BLACK)?.painted().into())
+    }
+
+    /// The registration identifier painted from a white start, as it was
+    /// painted.
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(Answer::Text(self.paint(program, Colour::WHITE)?.render()))
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    aoc_common::run(&Day11 { image: args.image, scale: args.scale }, &args.options);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_paint()
+    {
+        // Paints the start white, turns right and halts
+        let hull = paint(vec![104, 1, 104, 1, 99], Colour::BLACK).unwrap();
+        assert_eq!(hull.colour((0, 0)), Colour::WHITE);
+
+        assert!(matches!(paint(vec![104, 1, 104, 1, 104, 0, 99], Colour::BLACK), Err(PaintError::UnpairedColour(0))));
+    }
+}
@@ -0,0 +1,6 @@
+use clap::Parser;
+
+fn main()
+{
+    day_11::run(day_11::Args::parse());
+}
@@ -0,0 +1,209 @@
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+use intcode::{State, Vm};
+
+mod screen;
+use screen::{InvalidTile, Screen, Tile};
+
+/// Writing this to address 0 sets the cabinet to free play.
+const QUARTERS_ADDRESS: usize = 0;
+const FREE_PLAY: i64 = 2;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// Insert quarters (set address 0 to 2) without playing part two
+    #[arg(short, long, default_value_t=false)]
+    free_play: bool,
+
+    /// Steer the paddle from the keyboard instead of the autopilot: a to
+    /// move left, d to move right, anything else to stay
+    #[arg(short, long, default_value_t=false)]
+    interactive: bool,
+
+    /// Draw every frame in the terminal
+    #[arg(short, long, default_value_t=false)]
+    show: bool,
+}
+
+#[derive(Debug)]
+enum GameError
+{
+    Program(intcode::Error),
+    Screen(InvalidTile),
+    Io(io::Error),
+}
+
+impl Display for GameError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            GameError::Program(e) => write!(f, "{}", e),
+            GameError::Screen(e) => write!(f, "{}", e),
+            GameError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<intcode::Error> for GameError
+{
+    fn from(e: intcode::Error) -> Self
+    {
+        GameError::Program(e)
+    }
+}
+
+impl From<InvalidTile> for GameError
+{
+    fn from(e: InvalidTile) -> Self
+    {
+        GameError::Screen(e)
+    }
+}
+
+impl From<io::Error> for GameError
+{
+    fn from(e: io::Error) -> Self
+    {
+        GameError::Io(e)
+    }
+}
+
+/// Moves the paddle towards the ball.
+fn autopilot(screen: &Screen) -> i64
+{
+    match (screen.find(Tile::BALL), screen.find(Tile::PADDLE))
+    {
+        (Some((ball, _)), Some((paddle, _))) => (ball - paddle).signum(),
+        _ => 0,
+    }
+}
+
+fn joystick(lines: &mut impl Iterator<Item = io::Result<String>>) -> Result<i64, GameError>
+{
+    print!("Joystick (a/s/d): ");
+    io::stdout().flush()?;
+
+    let line = match lines.next()
+    {
+        Some(line) => line?,
+        None => return Err(GameError::Program(intcode::Error::InputExhausted)),
+    };
+    Ok(match line.trim()
+    {
+        "a" => -1,
+        "d" => 1,
+        _ => 0,
+    })
+}
+
+fn draw(screen: &Screen)
+{
+    // Clears the terminal before drawing the frame
+    print!("\x1b[2J\x1b[H{}", screen.render());
+}
+
+/// Runs the cabinet until the game ends, asking for a joystick position
+/// whenever the program wants one.
+fn play(program: Vec<i64>, day: &Day13) -> Result<Screen, GameError>
+{
+    let mut vm = Vm::new(program);
+    let mut screen = Screen::default();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop
+    {
+        let state = vm.run()?;
+        screen.update(&vm.take_output())?;
+        if day.show || day.interactive
+        {
+            draw(&screen);
+        }
+
+        match state
+        {
+            State::Halted => break,
+            State::NeedInput =>
+            {
+                let position = if day.interactive { joystick(&mut lines)? } else { autopilot(&screen) };
+                vm.push_input(position);
+            }
+        }
+    }
+
+    Ok(screen)
+}
+
+/// The arcade cabinet, played by the autopilot unless `interactive` is set.
+#[derive(Default)]
+pub struct Day13
+{
+    pub free_play: bool,
+    pub interactive: bool,
+    pub show: bool,
+}
+
+impl Day13
+{
+    fn play(&self, program: &[i64], free_play: bool) -> Result<Screen, Error>
+    {
+        let mut program = program.to_vec();
+        if free_play && !program.is_empty()
+        {
+            program[QUARTERS_ADDRESS] = FREE_PLAY;
+        }
+
+        play(program, self).map_err(|e| Error::Solve(format!("game failed, {}", e)))
+    }
+}
+
+impl Solution for Day13
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// The blocks on the screen when the game starts.
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(self.play(program, self.free_play)?.count(Tile::BLOCK).into())
+    }
+
+    /// The score once the game is won on free play.
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        let screen = self.play(program, true)?;
+        let blocks = screen.count(Tile::BLOCK);
+        if blocks > 0
+        {
+            return Err(Error::Solve(format!("game over with {} block(s) left at a score of {}", blocks, screen.score)));
+        }
+
+        Ok(screen.score.into())
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    aoc_common::run(&Day13 { free_play: args.free_play, interactive: args.interactive, show: args.show }, &args.options);
+}
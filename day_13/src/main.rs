@@ -0,0 +1,6 @@
+use clap::Parser;
+
+fn main()
+{
+    day_13::run(day_13::Args::parse());
+}
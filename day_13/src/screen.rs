@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile
+{
+    EMPTY = 0,
+    WALL = 1,
+    BLOCK = 2,
+    PADDLE = 3,
+    BALL = 4,
+}
+
+impl Tile
+{
+    pub fn new(value: i64) -> Option<Self>
+    {
+        match value
+        {
+            0 => Some(Tile::EMPTY),
+            1 => Some(Tile::WALL),
+            2 => Some(Tile::BLOCK),
+            3 => Some(Tile::PADDLE),
+            4 => Some(Tile::BALL),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> char
+    {
+        match self
+        {
+            Tile::EMPTY => ' ',
+            Tile::WALL => '#',
+            Tile::BLOCK => '=',
+            Tile::PADDLE => '_',
+            Tile::BALL => 'o',
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidTile(pub i64);
+
+impl Display for InvalidTile
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "invalid tile id {}", self.0)
+    }
+}
+
+/// What the cabinet has drawn so far.
+#[derive(Debug, Default)]
+pub struct Screen
+{
+    tiles: HashMap<(i64, i64), Tile>,
+    pub score: i64,
+    /// Outputs of a triple the program has not finished yet.
+    pending: Vec<i64>,
+}
+
+impl Screen
+{
+    /// Applies the program's outputs, `(x, y, tile)` triples and
+    /// `(-1, 0, score)` updates. A triple may be split across calls.
+    pub fn update(&mut self, outputs: &[i64]) -> Result<(), InvalidTile>
+    {
+        self.pending.extend_from_slice(outputs);
+        let complete = self.pending.len() - self.pending.len() % 3;
+
+        for triple in self.pending.drain(..complete).collect::<Vec<_>>().chunks(3)
+        {
+            match (triple[0], triple[1], triple[2])
+            {
+                (-1, 0, score) => self.score = score,
+                (x, y, id) =>
+                {
+                    let tile = Tile::new(id).ok_or(InvalidTile(id))?;
+                    self.tiles.insert((x, y), tile);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn count(&self, tile: Tile) -> usize
+    {
+        self.tiles.values().filter(|&&t| t == tile).count()
+    }
+
+    /// Position of some tile of this kind, there is only one ball and one
+    /// paddle.
+    pub fn find(&self, tile: Tile) -> Option<(i64, i64)>
+    {
+        self.tiles.iter().find(|&(_, &t)| t == tile).map(|(&position, _)| position)
+    }
+
+    pub fn render(&self) -> String
+    {
+        let max_x = self.tiles.keys().map(|&(x, _)| x).max().unwrap_or(-1);
+        let max_y = self.tiles.keys().map(|&(_, y)| y).max().unwrap_or(-1);
+
+        let mut text = format!("Score: {}\n", self.score);
+        for y in 0..=max_y
+        {
+            for x in 0..=max_x
+            {
+                text.push(self.tiles.get(&(x, y)).unwrap_or(&Tile::EMPTY).symbol());
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+use intcode::{State, Vm};
+
+mod map;
+use map::{Cell, Map, Move};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// Print the explored map
+    #[arg(short, long, default_value_t=false)]
+    render: bool,
+}
+
+#[derive(Debug)]
+enum DroidError
+{
+    Program(intcode::Error),
+    /// The droid stopped running or answered with something other than a
+    /// single status code.
+    NoStatus,
+    InvalidStatus(i64),
+    NoOxygen,
+}
+
+impl Display for DroidError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            DroidError::Program(e) => write!(f, "{}", e),
+            DroidError::NoStatus => write!(f, "droid did not report a status"),
+            DroidError::InvalidStatus(status) => write!(f, "droid reported status {}", status),
+            DroidError::NoOxygen => write!(f, "the oxygen system was not found"),
+        }
+    }
+}
+
+impl From<intcode::Error> for DroidError
+{
+    fn from(e: intcode::Error) -> Self
+    {
+        DroidError::Program(e)
+    }
+}
+
+/// Sends one movement command and waits for the droid's status.
+fn command(vm: &mut Vm, direction: Move) -> Result<Cell, DroidError>
+{
+    vm.push_input(direction as i64);
+    if vm.run()? != State::NeedInput
+    {
+        return Err(DroidError::NoStatus);
+    }
+
+    match vm.take_output()[..]
+    {
+        [status] => Cell::new(status).ok_or(DroidError::InvalidStatus(status)),
+        _ => Err(DroidError::NoStatus),
+    }
+}
+
+/// Maps the whole area breadth first. Every frontier cell keeps its own
+/// copy of the droid, so exploring a neighbour is one clone and one
+/// command, with no backtracking.
+fn explore(program: Vec<i64>) -> Result<Map, DroidError>
+{
+    let mut droid = Vm::new(program);
+    if droid.run()? != State::NeedInput
+    {
+        return Err(DroidError::NoStatus);
+    }
+
+    let mut map = Map::default();
+    map.cells.insert((0, 0), Cell::OPEN);
+    let mut queue = VecDeque::from([((0, 0), droid)]);
+    while let Some((position, droid)) = queue.pop_front()
+    {
+        for direction in Move::ALL
+        {
+            let next = direction.step(position);
+            if map.cells.contains_key(&next)
+            {
+                continue;
+            }
+
+            let mut moved = droid.clone();
+            let cell = command(&mut moved, direction)?;
+            map.cells.insert(next, cell);
+            if cell != Cell::WALL
+            {
+                queue.push_back((next, moved));
+            }
+        }
+    }
+
+    Ok(map)
+}
+
+fn minutes(program: Vec<i64>, render: bool, part_two: bool) -> Result<usize, DroidError>
+{
+    let map = explore(program)?;
+    if render
+    {
+        print!("{}", map.render());
+    }
+
+    let oxygen = map.oxygen().ok_or(DroidError::NoOxygen)?;
+    if part_two
+    {
+        // Minutes for oxygen to reach the furthest open cell
+        Ok(map.distances(oxygen).into_values().max().unwrap_or(0))
+    }
+    else
+    {
+        map.distances((0, 0)).get(&oxygen).copied().ok_or(DroidError::NoOxygen)
+    }
+}
+
+/// The repair droid. `render` prints the map once it is explored.
+#[derive(Default)]
+pub struct Day15
+{
+    pub render: bool,
+}
+
+impl Day15
+{
+    fn minutes(&self, program: &[i64], part_two: bool) -> Result<Answer, Error>
+    {
+        minutes(program.to_vec(), self.render, part_two)
+            .map(Answer::from)
+            .map_err(|e| Error::Solve(format!("droid failed, {}", e)))
+    }
+}
+
+impl Solution for Day15
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// The fewest moves to the oxygen system.
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        self.minutes(program, false)
+    }
+
+    /// The minutes it takes oxygen to fill the area.
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        self.minutes(program, true)
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    aoc_common::run(&Day15 { render: args.render }, &args.options);
+}
@@ -0,0 +1,6 @@
+use clap::Parser;
+
+fn main()
+{
+    day_15::run(day_15::Args::parse());
+}
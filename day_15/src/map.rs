@@ -0,0 +1,119 @@
+use std::collections::{HashMap, VecDeque};
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell
+{
+    WALL = 0,
+    OPEN = 1,
+    OXYGEN = 2,
+}
+
+impl Cell
+{
+    pub fn new(status: i64) -> Option<Self>
+    {
+        match status
+        {
+            0 => Some(Cell::WALL),
+            1 => Some(Cell::OPEN),
+            2 => Some(Cell::OXYGEN),
+            _ => None,
+        }
+    }
+}
+
+/// Movement commands in the order the droid numbers them.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move
+{
+    NORTH = 1,
+    SOUTH = 2,
+    WEST = 3,
+    EAST = 4,
+}
+
+impl Move
+{
+    pub const ALL: [Move; 4] = [Move::NORTH, Move::SOUTH, Move::WEST, Move::EAST];
+
+    /// The position next to `(x, y)` in this direction, y growing downwards.
+    pub fn step(self, (x, y): (i64, i64)) -> (i64, i64)
+    {
+        match self
+        {
+            Move::NORTH => (x, y - 1),
+            Move::SOUTH => (x, y + 1),
+            Move::WEST => (x - 1, y),
+            Move::EAST => (x + 1, y),
+        }
+    }
+}
+
+/// Everything the droid has seen, the droid started at (0, 0).
+#[derive(Debug, Default)]
+pub struct Map
+{
+    pub cells: HashMap<(i64, i64), Cell>,
+}
+
+impl Map
+{
+    pub fn oxygen(&self) -> Option<(i64, i64)>
+    {
+        self.cells.iter().find(|&(_, &cell)| cell == Cell::OXYGEN).map(|(&position, _)| position)
+    }
+
+    /// Number of moves from `start` to every reachable cell.
+    pub fn distances(&self, start: (i64, i64)) -> HashMap<(i64, i64), usize>
+    {
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(position) = queue.pop_front()
+        {
+            let distance = distances[&position];
+            for direction in Move::ALL
+            {
+                let next = direction.step(position);
+                let open = matches!(self.cells.get(&next), Some(Cell::OPEN | Cell::OXYGEN));
+                if open && !distances.contains_key(&next)
+                {
+                    distances.insert(next, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// '#' for walls, '.' for open cells, 'O' for the oxygen system, 'D' for
+    /// where the droid started and ' ' for anything unexplored.
+    pub fn render(&self) -> String
+    {
+        let min_x = self.cells.keys().map(|&(x, _)| x).min().unwrap_or(0);
+        let max_x = self.cells.keys().map(|&(x, _)| x).max().unwrap_or(0);
+        let min_y = self.cells.keys().map(|&(_, y)| y).min().unwrap_or(0);
+        let max_y = self.cells.keys().map(|&(_, y)| y).max().unwrap_or(0);
+
+        let mut text = String::new();
+        for y in min_y..=max_y
+        {
+            for x in min_x..=max_x
+            {
+                text.push(match self.cells.get(&(x, y))
+                {
+                    _ if (x, y) == (0, 0) => 'D',
+                    Some(Cell::WALL) => '#',
+                    Some(Cell::OPEN) => '.',
+                    Some(Cell::OXYGEN) => 'O',
+                    None => ' ',
+                });
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+}
@@ -0,0 +1,238 @@
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction
+{
+    UP,
+    RIGHT,
+    DOWN,
+    LEFT,
+}
+
+impl Direction
+{
+    fn new(symbol: u8) -> Option<Self>
+    {
+        match symbol
+        {
+            b'^' => Some(Direction::UP),
+            b'>' => Some(Direction::RIGHT),
+            b'v' => Some(Direction::DOWN),
+            b'<' => Some(Direction::LEFT),
+            _ => None,
+        }
+    }
+
+    fn turn_left(self) -> Self
+    {
+        match self
+        {
+            Direction::UP => Direction::LEFT,
+            Direction::LEFT => Direction::DOWN,
+            Direction::DOWN => Direction::RIGHT,
+            Direction::RIGHT => Direction::UP,
+        }
+    }
+
+    fn turn_right(self) -> Self
+    {
+        match self
+        {
+            Direction::UP => Direction::RIGHT,
+            Direction::RIGHT => Direction::DOWN,
+            Direction::DOWN => Direction::LEFT,
+            Direction::LEFT => Direction::UP,
+        }
+    }
+
+    fn step(self, (x, y): (i64, i64)) -> (i64, i64)
+    {
+        match self
+        {
+            Direction::UP => (x, y - 1),
+            Direction::RIGHT => (x + 1, y),
+            Direction::DOWN => (x, y + 1),
+            Direction::LEFT => (x - 1, y),
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn
+{
+    LEFT,
+    RIGHT,
+    /// Only the first move can go straight on, when the robot already
+    /// faces along the scaffold.
+    NONE,
+}
+
+/// One movement command, a turn followed by a number of steps forward.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move
+{
+    pub turn: Turn,
+    pub steps: usize,
+}
+
+impl std::fmt::Display for Move
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self.turn
+        {
+            Turn::LEFT => write!(f, "L,{}", self.steps),
+            Turn::RIGHT => write!(f, "R,{}", self.steps),
+            Turn::NONE => write!(f, "{}", self.steps),
+        }
+    }
+}
+
+/// The camera image, '#' for scaffold and the robot drawn as ^, >, v or <.
+pub struct Grid
+{
+    rows: Vec<Vec<u8>>,
+}
+
+impl Grid
+{
+    pub fn parse(text: &str) -> Self
+    {
+        let rows = text.lines().filter(|line| !line.is_empty()).map(|line| line.bytes().collect()).collect();
+
+        Grid { rows }
+    }
+
+    fn get(&self, (x, y): (i64, i64)) -> Option<u8>
+    {
+        let row = self.rows.get(usize::try_from(y).ok()?)?;
+        row.get(usize::try_from(x).ok()?).copied()
+    }
+
+    pub fn is_scaffold(&self, position: (i64, i64)) -> bool
+    {
+        matches!(self.get(position), Some(b'#' | b'^' | b'>' | b'v' | b'<'))
+    }
+
+    /// Scaffold cells with scaffold on all four sides.
+    pub fn intersections(&self) -> Vec<(i64, i64)>
+    {
+        let mut intersections = vec![];
+        for (y, row) in self.rows.iter().enumerate()
+        {
+            for x in 0..row.len()
+            {
+                let position = (x as i64, y as i64);
+                let neighbours = [Direction::UP, Direction::RIGHT, Direction::DOWN, Direction::LEFT]
+                    .iter()
+                    .all(|direction| self.is_scaffold(direction.step(position)));
+                if self.is_scaffold(position) && neighbours
+                {
+                    intersections.push(position);
+                }
+            }
+        }
+
+        intersections
+    }
+
+    pub fn alignment_sum(&self) -> i64
+    {
+        self.intersections().iter().map(|&(x, y)| x * y).sum()
+    }
+
+    pub fn robot(&self) -> Option<((i64, i64), Direction)>
+    {
+        self.rows.iter().enumerate().find_map(|(y, row)|
+            row.iter().enumerate().find_map(|(x, &symbol)|
+                Direction::new(symbol).map(|direction| ((x as i64, y as i64), direction))))
+    }
+
+    /// The route from the robot to the end of the scaffold, going straight
+    /// through every intersection.
+    pub fn path(&self) -> Vec<Move>
+    {
+        let (mut position, mut direction) = match self.robot()
+        {
+            Some(robot) => robot,
+            None => return vec![],
+        };
+
+        // Every move walks to the end of a straight, so only the first one
+        // can have scaffold right ahead.
+        let mut moves = vec![];
+        loop
+        {
+            let turn = if moves.is_empty() && self.is_scaffold(direction.step(position))
+            {
+                Turn::NONE
+            }
+            else if self.is_scaffold(direction.turn_left().step(position))
+            {
+                Turn::LEFT
+            }
+            else if self.is_scaffold(direction.turn_right().step(position))
+            {
+                Turn::RIGHT
+            }
+            else
+            {
+                return moves;
+            };
+            direction = match turn
+            {
+                Turn::LEFT => direction.turn_left(),
+                Turn::RIGHT => direction.turn_right(),
+                Turn::NONE => direction,
+            };
+
+            let mut steps = 0;
+            while self.is_scaffold(direction.step(position))
+            {
+                position = direction.step(position);
+                steps += 1;
+            }
+            moves.push(Move { turn, steps });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// The calibration image from the day 17 puzzle text.
+    const IMAGE: &str = "\
+..#..........
+..#..........
+#######...###
+#.#...#...#.#
+#############
+..#...#...#..
+..#####...^..
+";
+
+    fn path(grid: &Grid) -> String
+    {
+        grid.path().iter().map(|m| m.to_string()).collect::<Vec<_>>().join(",")
+    }
+
+    #[test]
+    fn test_alignment_sum()
+    {
+        let grid = Grid::parse(IMAGE);
+
+        assert_eq!(grid.intersections(), vec![(2, 2), (2, 4), (6, 4), (10, 4)]);
+        assert_eq!(grid.alignment_sum(), 76);
+    }
+
+    #[test]
+    fn test_path()
+    {
+        // The robot faces along the scaffold, so the first move goes straight
+        assert_eq!(path(&Grid::parse(IMAGE)), "4,R,2,R,2,R,12,R,2,R,6,R,4,R,4,R,6");
+        assert_eq!(path(&Grid::parse(&IMAGE.replace('^', ">"))), "L,4,R,2,R,2,R,12,R,2,R,6,R,4,R,4,R,6");
+        assert!(Grid::parse("...\n.#.\n").path().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+use intcode::{ascii, State, Vm};
+
+mod camera;
+mod routine;
+use camera::Grid;
+use routine::compress;
+
+/// Writing this to address 0 wakes the robot up.
+const WAKE_UP_ADDRESS: usize = 0;
+const WAKE_UP: i64 = 2;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// Print the camera image, and in part two the path and routines
+    #[arg(short, long, default_value_t=false)]
+    verbose: bool,
+}
+
+/// Runs the camera and returns the image it prints.
+fn camera(program: Vec<i64>) -> Result<String, intcode::Error>
+{
+    let mut vm = Vm::new(program);
+    if vm.run()? == State::NeedInput
+    {
+        return Err(intcode::Error::InputExhausted);
+    }
+    let (image, _) = ascii::split(&vm.take_output());
+
+    Ok(image)
+}
+
+/// Wakes the robot up, sends it the movement logic with the video feed
+/// off and returns the dust it reports.
+fn wake_up(mut program: Vec<i64>, lines: &[String]) -> Result<Option<i64>, intcode::Error>
+{
+    program[WAKE_UP_ADDRESS] = WAKE_UP;
+    let mut vm = Vm::new(program);
+    for line in lines
+    {
+        ascii::push_line(&mut vm, line);
+    }
+    ascii::push_line(&mut vm, "n");
+    if vm.run()? == State::NeedInput
+    {
+        return Err(intcode::Error::InputExhausted);
+    }
+    let (_, values) = ascii::split(&vm.take_output());
+
+    Ok(values.last().copied())
+}
+
+/// The vacuum robot. `verbose` prints the camera image, and in part two
+/// the path and routines.
+#[derive(Default)]
+pub struct Day17
+{
+    pub verbose: bool,
+}
+
+impl Day17
+{
+    fn grid(&self, program: &[i64]) -> Result<Grid, Error>
+    {
+        let image = camera(program.to_vec()).map_err(|e| Error::Solve(format!("camera failed, {}", e)))?;
+        if self.verbose
+        {
+            print!("{}", image);
+        }
+
+        Ok(Grid::parse(&image))
+    }
+}
+
+impl Solution for Day17
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// The sum of the alignment parameters of the scaffold.
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(self.grid(program)?.alignment_sum().into())
+    }
+
+    /// The dust the robot collects walking all of the scaffold.
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        let path = self.grid(program)?.path();
+        let routines = compress(&path).ok_or_else(|| Error::Solve("the path does not fit in three functions".to_string()))?;
+        let lines = routines.lines();
+        if self.verbose
+        {
+            println!("Path: {}", path.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(","));
+            for (name, line) in ["Main", "A", "B", "C"].iter().zip(&lines)
+            {
+                println!("{}: {}", name, line);
+            }
+        }
+
+        match wake_up(program.to_vec(), &lines)
+        {
+            Ok(Some(dust)) => Ok(dust.into()),
+            Ok(None) => Err(Error::Solve("the robot did not report any dust".to_string())),
+            Err(e) => Err(Error::Solve(format!("robot failed, {}", e))),
+        }
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    aoc_common::run(&Day17 { verbose: args.verbose }, &args.options);
+}
@@ -0,0 +1,6 @@
+use clap::Parser;
+
+fn main()
+{
+    day_17::run(day_17::Args::parse());
+}
@@ -0,0 +1,141 @@
+use crate::camera::Move;
+
+/// The robot's memory only fits this many characters per line, commas
+/// included.
+pub const MAX_LENGTH: usize = 20;
+
+/// Movement logic for the vacuum robot: a main routine calling functions
+/// A, B and C, which together walk the whole path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Routines
+{
+    pub main: Vec<usize>,
+    pub functions: Vec<Vec<Move>>,
+}
+
+impl Routines
+{
+    pub fn main_line(&self) -> String
+    {
+        self.main.iter().map(|&index| ((b'A' + index as u8) as char).to_string()).collect::<Vec<_>>().join(",")
+    }
+
+    /// The lines to send the robot, main routine first. Unused functions
+    /// are sent as `L,0`, a turn without a step, which is never called.
+    pub fn lines(&self) -> Vec<String>
+    {
+        let mut lines = vec![self.main_line()];
+        for index in 0..3
+        {
+            lines.push(match self.functions.get(index)
+            {
+                Some(function) => join(function),
+                None => "L,0".to_string(),
+            });
+        }
+
+        lines
+    }
+}
+
+fn join(moves: &[Move]) -> String
+{
+    moves.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Splits the path into a main routine and at most three functions that
+/// all fit in `MAX_LENGTH` characters. Every function starts at the first
+/// part of the path the previous ones do not cover, so only their lengths
+/// have to be searched.
+pub fn compress(path: &[Move]) -> Option<Routines>
+{
+    let mut functions = vec![];
+    let mut main = vec![];
+    if search(path, 0, &mut functions, &mut main)
+    {
+        Some(Routines { main, functions })
+    }
+    else
+    {
+        None
+    }
+}
+
+fn search(path: &[Move], position: usize, functions: &mut Vec<Vec<Move>>, main: &mut Vec<usize>) -> bool
+{
+    // Each call takes two characters in the main routine, comma included
+    if main.len() * 2 > MAX_LENGTH + 1
+    {
+        return false;
+    }
+    if position == path.len()
+    {
+        return true;
+    }
+
+    for index in 0..functions.len()
+    {
+        if path[position..].starts_with(&functions[index])
+        {
+            main.push(index);
+            if search(path, position + functions[index].len(), functions, main)
+            {
+                return true;
+            }
+            main.pop();
+        }
+    }
+
+    if functions.len() < 3
+    {
+        let mut length = 1;
+        while position + length <= path.len() && join(&path[position..position + length]).len() <= MAX_LENGTH
+        {
+            functions.push(path[position..position + length].to_vec());
+            main.push(functions.len() - 1);
+            if search(path, position + length, functions, main)
+            {
+                return true;
+            }
+            main.pop();
+            functions.pop();
+            length += 1;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::camera::Turn;
+
+    fn parse(text: &str) -> Vec<Move>
+    {
+        let tokens: Vec<&str> = text.split(',').collect();
+        tokens.chunks(2).map(|pair| Move
+        {
+            turn: if pair[0] == "R" { Turn::RIGHT } else { Turn::LEFT },
+            steps: pair[1].parse().unwrap(),
+        }).collect()
+    }
+
+    #[test]
+    fn test_compress()
+    {
+        // The path from the part two example in the puzzle text
+        let path = parse("R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2");
+        let routines = compress(&path).unwrap();
+
+        let expanded: Vec<Move> = routines.main.iter().flat_map(|&index| routines.functions[index].clone()).collect();
+        assert_eq!(expanded, path);
+        assert!(routines.functions.len() <= 3);
+        assert!(routines.lines().iter().all(|line| line.len() <= MAX_LENGTH));
+
+        // Nothing repeats, so three functions can't cover it
+        let path: Vec<Move> = (10..40).map(|steps| Move { turn: Turn::RIGHT, steps }).collect();
+        assert_eq!(compress(&path), None);
+    }
+}
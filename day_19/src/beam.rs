@@ -0,0 +1,160 @@
+use std::fmt::Display;
+
+use intcode::query::Query;
+
+/// No beam is flatter than this many columns per row, which bounds the
+/// search for a row's left edge when the row turns out to be empty.
+const MAX_SLOPE: i64 = 10;
+
+/// The search for a square gives up this many rows per side of the square
+/// below the emitter, as the beam would have to be extremely narrow.
+const MAX_DEPTH: i64 = 100;
+
+/// Only the first few rows next to the emitter can miss the beam, so this
+/// many empty rows in a row means there is no beam at all.
+const MAX_EMPTY_ROWS: i64 = 100;
+
+#[derive(Debug)]
+pub enum BeamError
+{
+    Program(intcode::Error),
+    Size(i64),
+    NoBeam(i64),
+    NoSquare(i64),
+}
+
+impl Display for BeamError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            BeamError::Program(e) => write!(f, "drone system failed, {}", e),
+            BeamError::Size(size) => write!(f, "the square has to be at least 1 wide, got {}", size),
+            BeamError::NoBeam(y) => write!(f, "no beam in the {} rows above row {}", MAX_EMPTY_ROWS, y),
+            BeamError::NoSquare(y) => write!(f, "no square fits in the beam above row {}", y),
+        }
+    }
+}
+
+impl From<intcode::Error> for BeamError
+{
+    fn from(e: intcode::Error) -> Self
+    {
+        BeamError::Program(e)
+    }
+}
+
+/// The drone system, asked one point per run.
+pub struct Beam
+{
+    pub query: Query,
+}
+
+impl Beam
+{
+    pub fn new(program: Vec<i64>) -> Self
+    {
+        Beam { query: Query::new(program) }
+    }
+
+    pub fn affected(&mut self, x: i64, y: i64) -> Result<bool, intcode::Error>
+    {
+        let output = self.query.ask(&[x, y])?;
+
+        Ok(output.last() == Some(&1))
+    }
+
+    /// Points pulled by the beam in the `size` by `size` area closest to the
+    /// emitter.
+    pub fn count(&mut self, size: i64) -> Result<usize, BeamError>
+    {
+        let mut count = 0;
+        for y in 0..size
+        {
+            for x in 0..size
+            {
+                if self.affected(x, y)?
+                {
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Top left corner of the square closest to the emitter that fits in
+    /// the beam.
+    pub fn closest_square(&mut self, size: i64) -> Result<(i64, i64), BeamError>
+    {
+        closest_square(|x, y| self.affected(x, y), size)
+    }
+}
+
+/// Walks the left edge of the beam down, row by row: a square whose bottom
+/// left corner sits on the edge fits as soon as its top right corner is in
+/// the beam too, so every row costs a couple of queries instead of one per
+/// point.
+fn closest_square(mut affected: impl FnMut(i64, i64) -> Result<bool, intcode::Error>, size: i64) -> Result<(i64, i64), BeamError>
+{
+    if size < 1
+    {
+        return Err(BeamError::Size(size));
+    }
+
+    let mut left = 0;
+    let mut empty_rows = 0;
+    for y in size - 1..size * MAX_DEPTH
+    {
+        let mut x = left;
+        while x <= MAX_SLOPE * (y + 1) && !affected(x, y)?
+        {
+            x += 1;
+        }
+
+        if x > MAX_SLOPE * (y + 1)
+        {
+            empty_rows += 1;
+            if empty_rows >= MAX_EMPTY_ROWS
+            {
+                return Err(BeamError::NoBeam(y));
+            }
+            continue;
+        }
+
+        empty_rows = 0;
+        left = x;
+        if affected(x + size - 1, y - size + 1)?
+        {
+            return Ok((x, y - size + 1));
+        }
+    }
+
+    Err(BeamError::NoSquare(size * MAX_DEPTH))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// Points between the diagonal and half of it, so every row y has
+    /// y / 2 + 1 points.
+    fn cone(x: i64, y: i64) -> Result<bool, intcode::Error>
+    {
+        Ok(2 * x >= y && x <= y)
+    }
+
+    #[test]
+    fn test_closest_square()
+    {
+        // Rows 6 to 8 all hold columns 4 to 6, and no earlier row fits three
+        assert!(matches!(closest_square(cone, 3), Ok((4, 6))));
+        assert!(matches!(closest_square(cone, 1), Ok((0, 0))));
+
+        assert!(matches!(closest_square(cone, 0), Err(BeamError::Size(0))));
+        assert!(matches!(closest_square(|x, y| Ok(x == y), 2), Err(BeamError::NoSquare(200))));
+        assert!(matches!(closest_square(|_, _| Ok(false), 2), Err(BeamError::NoBeam(_))));
+    }
+}
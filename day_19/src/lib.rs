@@ -0,0 +1,101 @@
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+
+mod beam;
+use beam::Beam;
+
+/// Side of the area scanned in part one, and of the ship in part two,
+/// unless told otherwise.
+const REGION: i64 = 50;
+const SIZE: i64 = 100;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// Side of the area scanned in part one
+    #[arg(short, long, default_value_t=REGION)]
+    region: i64,
+
+    /// Side of the ship that has to fit in the beam in part two
+    #[arg(short, long, default_value_t=SIZE)]
+    size: i64,
+
+    /// Print how many times the drone system was queried
+    #[arg(short, long, default_value_t=false)]
+    verbose: bool,
+}
+
+/// The tractor beam. `region` is the side of the area scanned in part one
+/// and `size` the side of the ship in part two.
+pub struct Day19
+{
+    pub region: i64,
+    pub size: i64,
+    pub verbose: bool,
+}
+
+impl Default for Day19
+{
+    fn default() -> Self
+    {
+        Day19 { region: REGION, size: SIZE, verbose: false }
+    }
+}
+
+impl Day19
+{
+    /// Runs `query` on the beam, printing how often it asked the drone
+    /// system when `verbose` is set.
+    fn query<T>(&self, program: &[i64], query: impl FnOnce(&mut Beam) -> Result<T, beam::BeamError>) -> Result<T, Error>
+    {
+        let mut beam = Beam::new(program.to_vec());
+        let result = query(&mut beam);
+        if self.verbose
+        {
+            println!("Queried the drone system {} times", beam.query.runs);
+        }
+
+        result.map_err(Error::solve)
+    }
+}
+
+impl Solution for Day19
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// The points pulled in the region closest to the emitter.
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        Ok(self.query(program, |beam| beam.count(self.region))?.into())
+    }
+
+    /// Where the closest square that fits in the beam starts.
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        let (x, y) = self.query(program, |beam| beam.closest_square(self.size))?;
+
+        Ok((x * 10000 + y).into())
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    aoc_common::run(&Day19 { region: args.region, size: args.size, verbose: args.verbose }, &args.options);
+}
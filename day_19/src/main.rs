@@ -0,0 +1,6 @@
+use clap::Parser;
+
+fn main()
+{
+    day_19::run(day_19::Args::parse());
+}
@@ -0,0 +1,316 @@
+//! Compiles boolean expressions over the sensors into springscript.
+//!
+//! The language has sensors `A` to `I`, `!` for not, `&` for and, `|` for
+//! or, in that order of precedence, and parentheses. `#` starts a comment
+//! running to the end of the line. The expression decides whether the
+//! droid jumps, for example
+//!
+//! ```text
+//! # Jump over any hole in reach, as long as there is ground to land on
+//! (!A | !B | !C) & D
+//! ```
+//!
+//! The result is computed into J, using T as the only scratch register.
+
+use crate::springscript::{Instruction, Mode, Op, Register, Script, ScriptError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr
+{
+    Sensor(usize),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+struct Parser
+{
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser
+{
+    /// Next character that is not whitespace or part of a comment.
+    fn peek(&mut self) -> Option<char>
+    {
+        while let Some(&c) = self.chars.get(self.position)
+        {
+            if c == '#'
+            {
+                while self.chars.get(self.position).is_some_and(|&c| c != '\n')
+                {
+                    self.position += 1;
+                }
+            }
+            else if c.is_whitespace()
+            {
+                self.position += 1;
+            }
+            else
+            {
+                return Some(c);
+            }
+        }
+
+        None
+    }
+
+    fn error(&self, message: &str) -> ScriptError
+    {
+        ScriptError::Parse(self.position, message.to_string())
+    }
+
+    fn expr(&mut self) -> Result<Expr, ScriptError>
+    {
+        let mut left = self.term()?;
+        while self.peek() == Some('|')
+        {
+            self.position += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.term()?));
+        }
+
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, ScriptError>
+    {
+        let mut left = self.factor()?;
+        while self.peek() == Some('&')
+        {
+            self.position += 1;
+            left = Expr::And(Box::new(left), Box::new(self.factor()?));
+        }
+
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ScriptError>
+    {
+        match self.peek()
+        {
+            Some('!') =>
+            {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.factor()?)))
+            }
+            Some('(') =>
+            {
+                self.position += 1;
+                let expr = self.expr()?;
+                if self.peek() != Some(')')
+                {
+                    return Err(self.error("expected ')'"));
+                }
+                self.position += 1;
+                Ok(expr)
+            }
+            Some(c @ ('A'..='I' | 'a'..='i')) =>
+            {
+                self.position += 1;
+                Ok(Expr::Sensor((c.to_ascii_uppercase() as u8 - b'A') as usize))
+            }
+            Some(_) => Err(self.error("expected a sensor, '!' or '('")),
+            None => Err(self.error("unexpected end of expression")),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Expr, ScriptError>
+{
+    let mut parser = Parser { chars: text.chars().collect(), position: 0 };
+    let expr = parser.expr()?;
+    if parser.peek().is_some()
+    {
+        return Err(parser.error("expected '&', '|' or the end of the expression"));
+    }
+
+    Ok(expr)
+}
+
+/// `(sensor, negated)` if the expression is a sensor or its negation.
+fn literal(expr: &Expr) -> Option<(usize, bool)>
+{
+    match expr
+    {
+        Expr::Sensor(index) => Some((*index, false)),
+        Expr::Not(inner) => match **inner
+        {
+            Expr::Sensor(index) => Some((index, true)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Splits a binary node into `(compound, literal)` if either side is a
+/// literal.
+fn with_literal<'a>(a: &'a Expr, b: &'a Expr) -> Option<(&'a Expr, (usize, bool))>
+{
+    literal(b).map(|lit| (a, lit)).or_else(|| literal(a).map(|lit| (b, lit)))
+}
+
+/// True if the expression can be computed in a single register: every
+/// operator has a literal on one side.
+fn is_chain(expr: &Expr) -> bool
+{
+    match expr
+    {
+        Expr::Sensor(_) => true,
+        Expr::Not(inner) => is_chain(inner),
+        Expr::And(a, b) | Expr::Or(a, b) => with_literal(a, b).is_some_and(|(rest, _)| is_chain(rest)),
+    }
+}
+
+struct Emitter
+{
+    instructions: Vec<Instruction>,
+}
+
+impl Emitter
+{
+    fn emit(&mut self, op: Op, x: Register, y: Register)
+    {
+        // Two inversions of the same register cancel out
+        let invert = Instruction { op: Op::NOT, x: y, y };
+        if op == Op::NOT && x == y && self.instructions.last() == Some(&invert)
+        {
+            self.instructions.pop();
+            return;
+        }
+        self.instructions.push(Instruction { op, x, y });
+    }
+
+    fn load(&mut self, (index, negated): (usize, bool), target: Register)
+    {
+        self.emit(Op::NOT, Register::Sensor(index), target);
+        if !negated
+        {
+            self.emit(Op::NOT, target, target);
+        }
+    }
+
+    /// Computes `expr` into `target` without touching the other register.
+    fn single(&mut self, expr: &Expr, target: Register) -> Result<(), ScriptError>
+    {
+        if let Some(lit) = literal(expr)
+        {
+            self.load(lit, target);
+            return Ok(());
+        }
+
+        match expr
+        {
+            Expr::Not(inner) =>
+            {
+                self.single(inner, target)?;
+                self.emit(Op::NOT, target, target);
+            }
+            Expr::And(a, b) | Expr::Or(a, b) =>
+            {
+                let op = if matches!(expr, Expr::And(..)) { Op::AND } else { Op::OR };
+                let (rest, (index, negated)) = with_literal(a, b).ok_or(ScriptError::TooComplex)?;
+                self.single(rest, target)?;
+                if negated
+                {
+                    // x & !s is !(!x | s), and x | !s is !(!x & s)
+                    let dual = if op == Op::AND { Op::OR } else { Op::AND };
+                    self.emit(Op::NOT, target, target);
+                    self.emit(dual, Register::Sensor(index), target);
+                    self.emit(Op::NOT, target, target);
+                }
+                else
+                {
+                    self.emit(op, Register::Sensor(index), target);
+                }
+            }
+            Expr::Sensor(_) => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    /// Computes `expr` into `target`, using `scratch` as needed.
+    fn double(&mut self, expr: &Expr, target: Register, scratch: Register) -> Result<(), ScriptError>
+    {
+        if let Some(lit) = literal(expr)
+        {
+            self.load(lit, target);
+            return Ok(());
+        }
+
+        match expr
+        {
+            Expr::Not(inner) =>
+            {
+                self.double(inner, target, scratch)?;
+                self.emit(Op::NOT, target, target);
+            }
+            Expr::And(a, b) | Expr::Or(a, b) =>
+            {
+                let op = if matches!(expr, Expr::And(..)) { Op::AND } else { Op::OR };
+                if let Some((rest, (index, negated))) = with_literal(a, b)
+                {
+                    self.double(rest, target, scratch)?;
+                    if negated
+                    {
+                        self.emit(Op::NOT, Register::Sensor(index), scratch);
+                        self.emit(op, scratch, target);
+                    }
+                    else
+                    {
+                        self.emit(op, Register::Sensor(index), target);
+                    }
+                }
+                else
+                {
+                    let (first, second) = if is_chain(b) { (a, b) } else { (b, a) };
+                    if !is_chain(second)
+                    {
+                        return Err(ScriptError::TooComplex);
+                    }
+                    self.double(first, target, scratch)?;
+                    self.single(second, scratch)?;
+                    self.emit(op, scratch, target);
+                }
+            }
+            Expr::Sensor(_) => unreachable!(),
+        }
+
+        Ok(())
+    }
+}
+
+/// Compiles the expression into a script that jumps when it holds.
+pub fn compile(expr: &Expr, mode: Mode) -> Result<Script, ScriptError>
+{
+    let mut emitter = Emitter { instructions: vec![] };
+    emitter.double(expr, Register::J, Register::T)?;
+
+    Script::new(emitter.instructions, mode)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_compile()
+    {
+        let expr = parse("(!A | !B | !C) & D & (E | H)  # part two").unwrap();
+        let script = compile(&expr, Mode::RUN).unwrap();
+        assert!(script.instructions.len() <= 15);
+
+        // Every combination of sensors agrees with the expression
+        for bits in 0..1 << 9
+        {
+            let sensors: Vec<bool> = (0..9).map(|index| bits & (1 << index) != 0).collect();
+            let [a, b, c, d, e, _, _, h, _] = sensors[..] else { unreachable!() };
+            assert_eq!(script.evaluate(&sensors), (!a || !b || !c) && d && (e || h));
+        }
+
+        assert_eq!(compile(&expr, Mode::WALK), Err(ScriptError::OutOfRange(Register::Sensor(4), Mode::WALK)));
+        assert!(matches!(parse("A & "), Err(ScriptError::Parse(4, _))));
+    }
+}
@@ -0,0 +1,159 @@
+use std::fs;
+
+use aoc_common::{Answer, Error, Options, Solution};
+use clap::Parser;
+use intcode::{ascii, State, Vm};
+
+mod compile;
+mod simulate;
+mod springscript;
+use simulate::{simulate, Outcome, RUN_HULLS, WALK_HULLS};
+use springscript::{Mode, Script};
+
+/// Jump when there is a hole in reach and ground to land on.
+const WALK_EXPRESSION: &str = "(!A | !B | !C) & D";
+/// Same, but only if the droid can move on or jump again after landing.
+const RUN_EXPRESSION: &str = "(!A | !B | !C) & D & (E | H)";
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Args
+{
+    #[command(flatten)]
+    options: Options,
+
+    /// File with the jump expression to compile instead of the built-in one
+    #[arg(short, long)]
+    script: Option<String>,
+
+    /// Extra hull to check the script against before sending it, e.g.
+    /// "#####.#..########"
+    #[arg(long)]
+    hull: Vec<String>,
+
+    /// Send the script even if it fails on one of the hulls
+    #[arg(short, long, default_value_t=false)]
+    force: bool,
+
+    /// Print the compiled springscript
+    #[arg(short, long, default_value_t=false)]
+    verbose: bool,
+}
+
+/// Checks the script against every hull, printing where it fails.
+fn check(script: &Script, hulls: &[String]) -> bool
+{
+    let mut passed = true;
+    for hull in hulls
+    {
+        if let Outcome::Fell(tile, jumps) = simulate(script, hull)
+        {
+            println!("Falls into the hole at {} of {} (jumped at {:?})", tile, hull, jumps);
+            passed = false;
+        }
+    }
+
+    passed
+}
+
+/// Sends the script to the droid and returns what it printed and the hull
+/// damage, if it made it across.
+fn survey(program: Vec<i64>, script: &Script) -> Result<(String, Option<i64>), intcode::Error>
+{
+    let mut vm = Vm::new(program);
+    ascii::push_text(&mut vm, &script.to_string());
+    if vm.run()? == State::NeedInput
+    {
+        return Err(intcode::Error::InputExhausted);
+    }
+    let (text, values) = ascii::split(&vm.take_output());
+
+    Ok((text, values.last().copied()))
+}
+
+/// The springdroid. `script` is a file with the jump expression to use
+/// instead of the built-in one, which is checked against `hull` along with
+/// the built-in hulls before it is sent, unless `force` is set.
+#[derive(Default)]
+pub struct Day21
+{
+    pub script: Option<String>,
+    pub hull: Vec<String>,
+    pub force: bool,
+    pub verbose: bool,
+}
+
+impl Day21
+{
+    /// Compiles the script for `mode`, checks it against `hulls` and the
+    /// extra ones and returns the hull damage the droid reports once it
+    /// made it across.
+    fn survey(&self, program: &[i64], mode: Mode, builtin: &str, hulls: &[&str]) -> Result<Answer, Error>
+    {
+        let source = match &self.script
+        {
+            Some(path) => fs::read_to_string(path).map_err(|e| Error::Solve(format!("could not load script from {}, {}", path, e)))?,
+            None => builtin.to_string(),
+        };
+
+        let script = compile::parse(&source)
+            .and_then(|expr| compile::compile(&expr, mode))
+            .map_err(|e| Error::Solve(format!("could not compile script, {}", e)))?;
+        if self.verbose
+        {
+            print!("{}", script);
+        }
+
+        let hulls: Vec<String> = hulls.iter().map(|hull| hull.to_string()).chain(self.hull.iter().cloned()).collect();
+        if !check(&script, &hulls) && !self.force
+        {
+            return Err(Error::Solve("script fails in simulation, use --force to send it anyway".to_string()));
+        }
+
+        match survey(program.to_vec(), &script)
+        {
+            Ok((_, Some(damage))) => Ok(damage.into()),
+            Ok((text, None)) =>
+            {
+                // The droid draws the hull it fell into, worth adding with --hull
+                print!("{}", text);
+                Err(Error::Solve("the droid fell into space".to_string()))
+            }
+            Err(e) => Err(Error::Solve(format!("program failed, {}", e))),
+        }
+    }
+}
+
+impl Solution for Day21
+{
+    type Input = Vec<i64>;
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
+
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        self.parse_bytes(text.as_bytes())
+    }
+
+    fn parse_bytes(&self, bytes: &[u8]) -> Result<Self::Input, Error>
+    {
+        intcode::load_bytes(bytes).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// The hull damage reported once the droid made it across walking.
+    fn part_one(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        self.survey(program, Mode::WALK, WALK_EXPRESSION, &WALK_HULLS)
+    }
+
+    /// The hull damage reported once the droid made it across running.
+    fn part_two(&self, program: &Self::Input) -> Result<Answer, Error>
+    {
+        self.survey(program, Mode::RUN, RUN_EXPRESSION, &RUN_HULLS)
+    }
+}
+
+/// Runs the solution with the options given on the command line.
+pub fn run(args: Args)
+{
+    aoc_common::run(&Day21 { script: args.script, hull: args.hull, force: args.force, verbose: args.verbose }, &args.options);
+}
@@ -0,0 +1,6 @@
+use clap::Parser;
+
+fn main()
+{
+    day_21::run(day_21::Args::parse());
+}
@@ -0,0 +1,76 @@
+use crate::springscript::{Script, JUMP_DISTANCE};
+
+/// Hulls the droid has to get across, '#' for ground and '.' for holes,
+/// with the droid starting on the first tile. Each one trips up some
+/// simpler script.
+pub const WALK_HULLS: [&str; 4] = [
+    "#####.###########",
+    "#####...#########",
+    "#####..#.########",
+    "#####.#..########",
+];
+pub const RUN_HULLS: [&str; 4] = [
+    "#####.#.##..#####",
+    "#####.##.##.#.###",
+    "#####...##.#.####",
+    "#####..####.#.###",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome
+{
+    Survived,
+    /// Fell into the hole at this tile, after the listed jumps.
+    Fell(usize, Vec<usize>),
+}
+
+/// Walks the droid across `hull` with the script deciding when to jump.
+/// Tiles past the end are ground.
+pub fn simulate(script: &Script, hull: &str) -> Outcome
+{
+    let ground = |tile: usize| hull.as_bytes().get(tile).is_none_or(|&b| b != b'.');
+
+    let mut position = 0;
+    let mut jumps = vec![];
+    while position < hull.len()
+    {
+        let sensors: Vec<bool> = (1..=script.mode.sensors()).map(|ahead| ground(position + ahead)).collect();
+        if script.evaluate(&sensors)
+        {
+            jumps.push(position);
+            position += JUMP_DISTANCE;
+        }
+        else
+        {
+            position += 1;
+        }
+
+        if !ground(position)
+        {
+            return Outcome::Fell(position, jumps);
+        }
+    }
+
+    Outcome::Survived
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::compile::{compile, parse};
+    use crate::springscript::Mode;
+
+    #[test]
+    fn test_simulate()
+    {
+        // Holes at 5, 6 and 8
+        let hull = "#####..#.####";
+        let script = |source: &str| compile(&parse(source).unwrap(), Mode::WALK).unwrap();
+
+        // Jumping only at the edge of a hole lands in the next one
+        assert_eq!(simulate(&script("!A"), hull), Outcome::Fell(8, vec![4]));
+        assert_eq!(simulate(&script("!A & D"), hull), Outcome::Fell(5, vec![]));
+        assert_eq!(simulate(&script("(!A | !B | !C) & D"), hull), Outcome::Survived);
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::Display;
+
+/// The droid only has memory for this many instructions.
+pub const MAX_INSTRUCTIONS: usize = 15;
+
+/// How far ahead the droid lands when it jumps.
+pub const JUMP_DISTANCE: usize = 4;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode
+{
+    WALK,
+    RUN,
+}
+
+impl Mode
+{
+    /// Number of tiles ahead the droid can see, A onwards.
+    pub fn sensors(&self) -> usize
+    {
+        match self
+        {
+            Mode::WALK => 4,
+            Mode::RUN => 9,
+        }
+    }
+}
+
+impl Display for Mode
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let text = match self
+        {
+            Mode::WALK => "WALK",
+            Mode::RUN => "RUN",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// A sensor, `Sensor(0)` being A one tile ahead, or one of the two
+/// writable registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register
+{
+    Sensor(usize),
+    T,
+    J,
+}
+
+impl Display for Register
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Register::Sensor(index) => write!(f, "{}", (b'A' + *index as u8) as char),
+            Register::T => write!(f, "T"),
+            Register::J => write!(f, "J"),
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op
+{
+    AND,
+    OR,
+    NOT,
+}
+
+impl Display for Op
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let text = match self
+        {
+            Op::AND => "AND",
+            Op::OR => "OR",
+            Op::NOT => "NOT",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// `op x y`, storing the result in `y`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction
+{
+    pub op: Op,
+    pub x: Register,
+    pub y: Register,
+}
+
+impl Display for Instruction
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "{} {} {}", self.op, self.x, self.y)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScriptError
+{
+    Parse(usize, String),
+    /// The expression needs more scratch registers than T and J.
+    TooComplex,
+    TooLong(usize),
+    /// The sensor can't be read in this mode.
+    OutOfRange(Register, Mode),
+}
+
+impl Display for ScriptError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            ScriptError::Parse(position, message) => write!(f, "at character {}: {}", position + 1, message),
+            ScriptError::TooComplex => write!(f, "expression needs more than the T and J registers"),
+            ScriptError::TooLong(length) => write!(f, "script has {} instructions, the droid only fits {}", length, MAX_INSTRUCTIONS),
+            ScriptError::OutOfRange(register, mode) => write!(f, "sensor {} can't be used in {} mode", register, mode),
+        }
+    }
+}
+
+/// A springscript program ready to be sent to the droid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Script
+{
+    pub instructions: Vec<Instruction>,
+    pub mode: Mode,
+}
+
+impl Script
+{
+    pub fn new(instructions: Vec<Instruction>, mode: Mode) -> Result<Self, ScriptError>
+    {
+        if instructions.len() > MAX_INSTRUCTIONS
+        {
+            return Err(ScriptError::TooLong(instructions.len()));
+        }
+        for instruction in &instructions
+        {
+            if let Register::Sensor(index) = instruction.x
+                && index >= mode.sensors()
+            {
+                return Err(ScriptError::OutOfRange(instruction.x, mode));
+            }
+        }
+
+        Ok(Script { instructions, mode })
+    }
+
+    /// Runs the script on what the sensors see, ground being true, and
+    /// returns whether the droid jumps. Both registers start out false.
+    pub fn evaluate(&self, sensors: &[bool]) -> bool
+    {
+        let mut t = false;
+        let mut j = false;
+        for instruction in &self.instructions
+        {
+            let x = match instruction.x
+            {
+                Register::Sensor(index) => sensors.get(index).copied().unwrap_or(true),
+                Register::T => t,
+                Register::J => j,
+            };
+            let y = if instruction.y == Register::T { &mut t } else { &mut j };
+            *y = match instruction.op
+            {
+                Op::AND => x && *y,
+                Op::OR => x || *y,
+                Op::NOT => !x,
+            };
+        }
+
+        j
+    }
+}
+
+/// One instruction per line, followed by WALK or RUN, as the droid reads it.
+impl Display for Script
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        for instruction in &self.instructions
+        {
+            writeln!(f, "{}", instruction)?;
+        }
+        writeln!(f, "{}", self.mode)
+    }
+}
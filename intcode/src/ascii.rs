@@ -0,0 +1,42 @@
+//! Helpers for programs that talk ASCII: every input and output value is a
+//! character code, except for outputs too large to be one, which carry the
+//! actual answer.
+
+use crate::vm::Vm;
+
+/// Queues `line` and the newline ending it as input.
+pub fn push_line(vm: &mut Vm, line: &str)
+{
+    for byte in line.bytes()
+    {
+        vm.push_input(byte as i64);
+    }
+    vm.push_input(b'\n' as i64);
+}
+
+/// Queues every line of `text` as input.
+pub fn push_text(vm: &mut Vm, text: &str)
+{
+    for line in text.lines()
+    {
+        push_line(vm, line);
+    }
+}
+
+/// Splits outputs into the text they spell and the values that are not
+/// ASCII.
+pub fn split(outputs: &[i64]) -> (String, Vec<i64>)
+{
+    let mut text = String::new();
+    let mut values = vec![];
+    for &value in outputs
+    {
+        match u8::try_from(value)
+        {
+            Ok(byte) if byte.is_ascii() => text.push(byte as char),
+            _ => values.push(value),
+        }
+    }
+
+    (text, values)
+}
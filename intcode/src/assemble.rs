@@ -0,0 +1,108 @@
+//! Assembler for the listing `decode::disassemble` prints, so a
+//! disassembled program can be edited and turned back into an image.
+//!
+//! Every line is one instruction, `NAME operands`, or a single `DATA`
+//! value, optionally preceded by its address as in `   12: ADD [4] 7 [9]`.
+//! Operands are written `x` in immediate mode, `[x]` in position mode and
+//! `[base+x]` in relative mode. Opcodes are looked up by name in an
+//! `OpcodeTable`, so registered custom instructions assemble like the
+//! built-in ones. Blank lines and lines starting with # are skipped.
+
+use crate::instruction::{Mode, Param};
+use crate::table::OpcodeTable;
+use crate::Error;
+
+fn operand(text: &str) -> Option<(Mode, i64)>
+{
+    let (mode, value) = match text.strip_prefix('[').and_then(|text| text.strip_suffix(']'))
+    {
+        Some(inner) => match inner.strip_prefix("base")
+        {
+            Some(offset) => (Mode::RELATIVE, offset.strip_prefix('+').unwrap_or(offset)),
+            None => (Mode::POSITION, inner),
+        },
+        None => (Mode::IMMEDIATE, text),
+    };
+
+    value.parse().ok().map(|value| (mode, value))
+}
+
+/// Assembles one line, without its address, into memory cells.
+fn line(text: &str, table: &OpcodeTable) -> Result<Vec<i64>, String>
+{
+    let mut words = text.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let operands: Vec<&str> = words.collect();
+
+    if name == "DATA"
+    {
+        return match operands[..]
+        {
+            [value] => value.parse().map(|value| vec![value]).map_err(|_| format!("'{}' is not a number", value)),
+            _ => Err("DATA takes one value".to_string()),
+        };
+    }
+
+    let (code, definition) = table.by_name(name).ok_or_else(|| format!("unknown opcode '{}'", name))?;
+    if operands.len() != definition.params.len()
+    {
+        return Err(format!("{} takes {} operand(s), got {}", name, definition.params.len(), operands.len()));
+    }
+
+    let mut cells = vec![code];
+    for (index, (&text, &param)) in operands.iter().zip(&definition.params).enumerate()
+    {
+        let (mode, value) = operand(text).ok_or_else(|| format!("'{}' is not an operand", text))?;
+        if param == Param::Write && mode == Mode::IMMEDIATE
+        {
+            return Err(format!("operand {} of {} is written, it needs an address", index + 1, name));
+        }
+        cells[0] += (mode as i64) * 10_i64.pow(index as u32 + 2);
+        cells.push(value);
+    }
+
+    Ok(cells)
+}
+
+pub fn assemble(source: &str, table: &OpcodeTable) -> Result<Vec<i64>, Error>
+{
+    let mut program = vec![];
+    for (index, text) in source.lines().enumerate()
+    {
+        // The address disassemble prints is only there for the reader.
+        let text = match text.split_once(':')
+        {
+            Some((address, rest)) if address.trim().parse::<usize>().is_ok() => rest,
+            _ => text,
+        };
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#')
+        {
+            continue;
+        }
+
+        program.extend(line(text, table).map_err(|reason| Error::Assemble(index + 1, reason))?);
+    }
+
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::decode::disassemble;
+
+    #[test]
+    fn test_assemble()
+    {
+        // Compares the input to 8, from the day 5 puzzle text
+        let program = vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let table = OpcodeTable::new();
+
+        assert_eq!(assemble(&disassemble(&program, &table), &table).unwrap(), program);
+        assert_eq!(assemble("ADD [base-3] 4 [7]\n# comment\nEXIT\n", &table).unwrap(), vec![1201, -3, 4, 7, 99]);
+        assert!(matches!(assemble("EXIT\nADD 1 2 3", &table), Err(Error::Assemble(2, _))));
+        assert!(matches!(assemble("INPUT [1] [2]", &table), Err(Error::Assemble(1, _))));
+    }
+}
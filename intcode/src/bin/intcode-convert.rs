@@ -0,0 +1,64 @@
+//! Converts an Intcode program between the text and the binary image
+//! format. The direction is picked from the input file.
+//!
+//! Usage: intcode-convert <input> <output>
+
+use std::env;
+use std::fs;
+use std::process;
+
+use intcode::image::{binary_to_text, is_binary, text_to_binary};
+
+fn main()
+{
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3
+    {
+        eprintln!("Usage: {} <input> <output>", args[0]);
+        process::exit(1);
+    }
+
+    let bytes = match fs::read(&args[1])
+    {
+        Ok(bytes) => bytes,
+        Err(e) =>
+        {
+            eprintln!("Error: could not load file {}, {}", args[1], e);
+            process::exit(1);
+        }
+    };
+
+    let converted = if is_binary(&bytes)
+    {
+        binary_to_text(&bytes).map(String::into_bytes)
+    }
+    else
+    {
+        match String::from_utf8(bytes)
+        {
+            Ok(text) => text_to_binary(&text),
+            Err(e) =>
+            {
+                eprintln!("Error: {} is neither a binary image nor text, {}", args[1], e);
+                process::exit(1);
+            }
+        }
+    };
+
+    let converted = match converted
+    {
+        Ok(converted) => converted,
+        Err(e) =>
+        {
+            eprintln!("Error: could not convert {}, {}", args[1], e);
+            process::exit(1);
+        }
+    };
+
+    if let Err(e) = fs::write(&args[2], &converted)
+    {
+        eprintln!("Error: could not write file {}, {}", args[2], e);
+        process::exit(1);
+    }
+    println!("Wrote {} bytes to {}", converted.len(), args[2]);
+}
@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use crate::instruction::{Instruction, Mode, Opcode};
+use crate::table::OpcodeTable;
+
+/// Static view of a program: every instruction that can be reached from
+/// address 0 by following fall-through and immediate jump targets, without
+/// executing anything.
+pub struct Listing
+{
+    pub instructions: BTreeMap<usize, Instruction>,
+    /// Cells occupied by a decoded instruction, opcode or parameter.
+    pub code: Vec<bool>,
+    /// Addresses written through a position-mode parameter.
+    pub writes: BTreeSet<usize>,
+    /// Reachable addresses that do not hold a complete, valid instruction.
+    pub invalid: BTreeSet<usize>,
+    /// True if some jump takes its target from memory, or some custom
+    /// instruction could jump wherever its handler decides.
+    pub dynamic_jumps: bool,
+    /// True if some instruction uses the relative base, whose writes can't
+    /// be resolved statically.
+    pub relative: bool,
+}
+
+impl Listing
+{
+    pub fn is_code(&self, address: usize) -> bool
+    {
+        self.code.get(address).copied().unwrap_or(false)
+    }
+
+    /// True if the program writes into one of its own decoded instructions
+    /// or into a reachable address that does not hold one yet.
+    pub fn is_self_modifying(&self) -> bool
+    {
+        self.writes.iter().any(|&address| self.is_code(address) || self.invalid.contains(&address))
+    }
+
+    /// Addresses execution can continue at after the instruction at
+    /// `address`. Jumps with a position-mode target contribute nothing.
+    pub fn successors(&self, program: &[i64], address: usize) -> Vec<usize>
+    {
+        let instruction = match self.instructions.get(&address)
+        {
+            Some(instruction) => instruction,
+            None => return vec![],
+        };
+        let next = address + instruction.size();
+
+        match instruction.opcode
+        {
+            Opcode::EXIT => vec![],
+            Opcode::JMP_EQ | Opcode::JMP_NE =>
+            {
+                let target = match instruction.second_param
+                {
+                    Mode::IMMEDIATE => usize::try_from(program[address + 2]).ok(),
+                    Mode::POSITION | Mode::RELATIVE => None,
+                };
+                let condition = match instruction.first_param
+                {
+                    Mode::IMMEDIATE => Some(program[address + 1] != 0),
+                    Mode::POSITION | Mode::RELATIVE => None,
+                };
+                let jump_on = instruction.opcode == Opcode::JMP_EQ;
+
+                match condition
+                {
+                    Some(c) if c == jump_on => target.into_iter().collect(),
+                    Some(_) => vec![next],
+                    None => target.into_iter().chain(std::iter::once(next)).collect(),
+                }
+            }
+            _ => vec![next],
+        }
+    }
+}
+
+pub fn decode(program: &[i64]) -> Listing
+{
+    decode_with(program, &OpcodeTable::new())
+}
+
+/// Like `decode`, but also recognises the custom opcodes in `table`. Their
+/// handlers are only known at run time, so a custom instruction counts as a
+/// jump to a computed address that may also fall through to the next one.
+pub fn decode_with(program: &[i64], table: &OpcodeTable) -> Listing
+{
+    decode_from(program, table, &[0])
+}
+
+/// Like `decode_with`, but follows the code reachable from every address in
+/// `entries` instead of only from address 0.
+pub fn decode_from(program: &[i64], table: &OpcodeTable, entries: &[usize]) -> Listing
+{
+    let mut listing = Listing
+    {
+        instructions: BTreeMap::new(),
+        code: vec![false; program.len()],
+        writes: BTreeSet::new(),
+        invalid: BTreeSet::new(),
+        dynamic_jumps: false,
+        relative: false,
+    };
+
+    let mut pending = entries.to_vec();
+    while let Some(address) = pending.pop()
+    {
+        if listing.instructions.contains_key(&address) || listing.invalid.contains(&address)
+        {
+            continue;
+        }
+
+        let instruction = match program.get(address).map(|&value| table.decode(value))
+        {
+            Some(Ok(instruction)) if address + instruction.size() <= program.len() => instruction,
+            _ =>
+            {
+                listing.invalid.insert(address);
+                continue;
+            }
+        };
+
+        for cell in address..address + instruction.size()
+        {
+            listing.code[cell] = true;
+        }
+        if instruction.opcode == Opcode::ADJUST_BASE
+            || (0..instruction.arity()).any(|index| instruction.mode(index) == Mode::RELATIVE)
+        {
+            listing.relative = true;
+        }
+        if let Some(index) = instruction.write_param()
+            && instruction.mode(index) != Mode::RELATIVE
+            && let Ok(target) = usize::try_from(program[address + 1 + index])
+        {
+            listing.writes.insert(target);
+        }
+        let computed_jump = match instruction.opcode
+        {
+            Opcode::JMP_EQ | Opcode::JMP_NE => instruction.second_param != Mode::IMMEDIATE,
+            Opcode::CUSTOM(_) => true,
+            _ => false,
+        };
+        if computed_jump
+        {
+            listing.dynamic_jumps = true;
+        }
+
+        listing.instructions.insert(address, instruction);
+        pending.extend(listing.successors(program, address));
+    }
+
+    listing
+}
+
+/// One line per decoded instruction, `address: NAME operands`, with
+/// position-mode operands in brackets and relative ones as `[base+x]`.
+/// Cells that are not code are listed as DATA.
+pub fn disassemble(program: &[i64], table: &OpcodeTable) -> String
+{
+    let listing = decode_with(program, table);
+    let mut out = String::new();
+
+    let mut address = 0;
+    while address < program.len()
+    {
+        match listing.instructions.get(&address)
+        {
+            Some(instruction) =>
+            {
+                write!(out, "{:>5}: {}", address, table.name(instruction.opcode)).unwrap();
+                for index in 0..instruction.arity()
+                {
+                    let value = program[address + 1 + index];
+                    match instruction.mode(index)
+                    {
+                        Mode::POSITION => write!(out, " [{}]", value).unwrap(),
+                        Mode::IMMEDIATE => write!(out, " {}", value).unwrap(),
+                        Mode::RELATIVE => write!(out, " [base{:+}]", value).unwrap(),
+                    }
+                }
+                writeln!(out).unwrap();
+                address += instruction.size();
+            }
+            None =>
+            {
+                writeln!(out, "{:>5}: DATA {}", address, program[address]).unwrap();
+                address += 1;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_decode()
+    {
+        // Compares the input to 8, from the day 5 puzzle text
+        let program = vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let listing = decode(&program);
+
+        assert_eq!(listing.instructions.keys().copied().collect::<Vec<_>>(), vec![0, 2, 6, 8]);
+        assert!(!listing.is_self_modifying());
+        assert!(!listing.is_code(9));
+
+        // Day 2 example writes into its own parameters
+        let listing = decode(&[1, 1, 1, 4, 99, 5, 6, 0, 99]);
+        assert!(listing.is_self_modifying());
+    }
+}
@@ -0,0 +1,126 @@
+//! Differences between a program image and memory after a run.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::record::Log;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change
+{
+    pub address: usize,
+    pub old: i64,
+    pub new: i64,
+    /// Address of the instruction that last wrote the cell, if the run was
+    /// recorded and the cell was written during it.
+    pub writer: Option<usize>,
+}
+
+/// A run of consecutive changed cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Range
+{
+    pub changes: Vec<Change>,
+}
+
+impl Range
+{
+    pub fn start(&self) -> usize
+    {
+        self.changes[0].address
+    }
+
+    /// One past the last changed address.
+    pub fn end(&self) -> usize
+    {
+        self.changes[self.changes.len() - 1].address + 1
+    }
+}
+
+impl Display for Range
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        writeln!(f, "[{}..{})", self.start(), self.end())?;
+        for change in &self.changes
+        {
+            let writer = match change.writer
+            {
+                Some(pc) => format!("written by pc {}", pc),
+                None => "not written by the program".to_string(),
+            };
+            writeln!(f, "    [{}] {} => {} ({})", change.address, change.old, change.new, writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Every cell of `memory` that differs from `image`, grouped into
+/// contiguous ranges. Cells past the end of the image count as zero. If
+/// `log` is the recording of the run, each change is annotated with the
+/// instruction that last wrote it.
+pub fn diff(image: &[i64], memory: &[i64], log: Option<&Log>) -> Vec<Range>
+{
+    // Later steps overwrite earlier ones, leaving the last writer of each
+    // cell.
+    let writers: HashMap<usize, usize> = log
+        .map(|log| log.steps.iter().filter_map(|step| step.write.map(|write| (write.address, step.pc))).collect())
+        .unwrap_or_default();
+    let mut ranges: Vec<Range> = vec![];
+
+    for address in 0..image.len().max(memory.len())
+    {
+        let old = image.get(address).copied().unwrap_or(0);
+        let new = memory.get(address).copied().unwrap_or(0);
+        if old == new
+        {
+            continue;
+        }
+
+        let writer = writers.get(&address).copied();
+        let change = Change { address, old, new, writer };
+
+        match ranges.last_mut()
+        {
+            Some(range) if range.end() == address => range.changes.push(change),
+            _ => ranges.push(Range { changes: vec![change] }),
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::Vm;
+
+    #[test]
+    fn test_diff()
+    {
+        let image = vec![
+            1101, 1, 2, 13,     // 0: [13] = 3
+            1101, 3, 4, 13,     // 4: [13] = 7
+            1101, 0, 5, 14,     // 8: [14] = 5
+            99,
+            0, 0,
+        ];
+        let mut vm = Vm::new(image.clone());
+        vm.record();
+        vm.run().unwrap();
+        vm.memory[2] = 9;
+
+        let ranges = diff(&image, &vm.memory, vm.log());
+        assert_eq!(ranges.iter().map(|range| (range.start(), range.end())).collect::<Vec<_>>(), vec![(2, 3), (13, 15)]);
+        assert_eq!(ranges[0].changes, vec![Change { address: 2, old: 2, new: 9, writer: None }]);
+        assert_eq!(ranges[1].changes, vec![
+            Change { address: 13, old: 0, new: 7, writer: Some(4) },
+            Change { address: 14, old: 0, new: 5, writer: Some(8) },
+        ]);
+
+        let ranges = diff(&image, &vm.memory, None);
+        assert!(ranges.iter().flat_map(|range| &range.changes).all(|change| change.writer.is_none()));
+    }
+}
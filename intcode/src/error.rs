@@ -0,0 +1,60 @@
+use std::fmt::Display;
+use std::io;
+
+use crate::load::LoadError;
+
+#[derive(Debug)]
+pub enum Error
+{
+    InvalidOpcode(i64),
+    InvalidMode(i64),
+    InvalidAddress(i64),
+    InputExhausted,
+    Parse(String),
+    Load(LoadError),
+    InvalidImage(String),
+    InvalidDefinition(String),
+    ReplayDiverged(usize),
+    /// A line of assembly that could not be assembled, 1-based.
+    Assemble(usize, String),
+    Io(io::Error),
+}
+
+impl Display for Error
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match self
+        {
+            Error::InvalidOpcode(value) => write!(f, "invalid opcode: {}", value),
+            Error::InvalidMode(value) => write!(f, "invalid parameter mode: {}", value),
+            Error::InvalidAddress(value) => write!(f, "invalid address: {}", value),
+            Error::InputExhausted => write!(f, "program asked for more input than was given"),
+            Error::Parse(text) => write!(f, "could not parse '{}'", text),
+            Error::Load(e) => write!(f, "{}", e),
+            Error::InvalidImage(reason) => write!(f, "invalid binary image: {}", reason),
+            Error::InvalidDefinition(reason) => write!(f, "invalid opcode definition: {}", reason),
+            Error::ReplayDiverged(step) => write!(f, "replay diverged from the log at step {}", step),
+            Error::Assemble(line, reason) => write!(f, "line {}: {}", line, reason),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error
+{
+    fn from(e: io::Error) -> Self
+    {
+        Error::Io(e)
+    }
+}
+
+impl From<LoadError> for Error
+{
+    fn from(e: LoadError) -> Self
+    {
+        Error::Load(e)
+    }
+}
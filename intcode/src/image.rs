@@ -0,0 +1,298 @@
+//! Compact binary format for Intcode programs.
+//!
+//! Layout, all integers LEB128 varints unless noted:
+//!
+//! - magic `INTC` (4 bytes)
+//! - version (1 byte), currently 1
+//! - cell width (1 byte): bytes needed for the widest cell, 1 to 8
+//! - flags (1 byte): bit 0 entry point present, bit 1 symbol table present
+//! - entry point, if flagged
+//! - symbol count, then per symbol: name length, UTF-8 name, address
+//! - cell count, then every cell zigzag encoded
+
+use std::fmt::Write;
+
+use crate::load::parse_program;
+use crate::Error;
+
+pub const MAGIC: &[u8; 4] = b"INTC";
+pub const VERSION: u8 = 1;
+
+const FLAG_ENTRY: u8 = 1;
+const FLAG_SYMBOLS: u8 = 2;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Image
+{
+    pub cells: Vec<i64>,
+    pub entry: usize,
+    pub symbols: Vec<(String, usize)>,
+}
+
+impl Image
+{
+    pub fn new(cells: Vec<i64>) -> Self
+    {
+        Image { cells, entry: 0, symbols: vec![] }
+    }
+}
+
+pub fn is_binary(bytes: &[u8]) -> bool
+{
+    bytes.starts_with(MAGIC)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64)
+{
+    loop
+    {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0
+        {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn zigzag(value: i64) -> u64
+{
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64
+{
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Bytes needed to store `value` as a two's complement integer.
+fn width(value: i64) -> u8
+{
+    (1..=8).find(|&bytes| value >> (bytes * 8 - 1) == 0 || value >> (bytes * 8 - 1) == -1).unwrap_or(8) as u8
+}
+
+pub fn encode(image: &Image) -> Vec<u8>
+{
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    out.push(image.cells.iter().map(|&cell| width(cell)).max().unwrap_or(1));
+
+    let mut flags = 0;
+    if image.entry != 0
+    {
+        flags |= FLAG_ENTRY;
+    }
+    if !image.symbols.is_empty()
+    {
+        flags |= FLAG_SYMBOLS;
+    }
+    out.push(flags);
+
+    if flags & FLAG_ENTRY != 0
+    {
+        write_varint(&mut out, image.entry as u64);
+    }
+    if flags & FLAG_SYMBOLS != 0
+    {
+        write_varint(&mut out, image.symbols.len() as u64);
+        for (name, address) in &image.symbols
+        {
+            write_varint(&mut out, name.len() as u64);
+            out.extend_from_slice(name.as_bytes());
+            write_varint(&mut out, *address as u64);
+        }
+    }
+
+    write_varint(&mut out, image.cells.len() as u64);
+    for &cell in &image.cells
+    {
+        write_varint(&mut out, zigzag(cell));
+    }
+
+    out
+}
+
+struct Reader<'a>
+{
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_>
+{
+    fn byte(&mut self) -> Result<u8, Error>
+    {
+        let byte = *self.bytes.get(self.position).ok_or_else(|| invalid("unexpected end of image"))?;
+        self.position += 1;
+
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, Error>
+    {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7)
+        {
+            let byte = self.byte()?;
+            // Only the lowest bit of the last byte still fits
+            if shift == 63 && byte & 0x7e != 0
+            {
+                return Err(invalid("varint overflows 64 bits"));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0
+            {
+                return Ok(value);
+            }
+        }
+
+        Err(invalid("varint is too long"))
+    }
+
+    fn usize(&mut self) -> Result<usize, Error>
+    {
+        usize::try_from(self.varint()?).map_err(|_| invalid("value does not fit in an address"))
+    }
+}
+
+fn invalid(reason: &str) -> Error
+{
+    Error::InvalidImage(reason.to_string())
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Image, Error>
+{
+    if !is_binary(bytes)
+    {
+        return Err(invalid("missing INTC magic"));
+    }
+    let mut reader = Reader { bytes, position: MAGIC.len() };
+
+    let version = reader.byte()?;
+    if version != VERSION
+    {
+        return Err(Error::InvalidImage(format!("unsupported version {}", version)));
+    }
+    let cell_width = reader.byte()?;
+    if !(1..=8).contains(&cell_width)
+    {
+        return Err(Error::InvalidImage(format!("unsupported cell width {}", cell_width)));
+    }
+    let flags = reader.byte()?;
+
+    let mut image = Image::default();
+    if flags & FLAG_ENTRY != 0
+    {
+        image.entry = reader.usize()?;
+    }
+    if flags & FLAG_SYMBOLS != 0
+    {
+        for _ in 0..reader.usize()?
+        {
+            let length = reader.usize()?;
+            let end = reader.position.checked_add(length).filter(|&end| end <= bytes.len())
+                .ok_or_else(|| invalid("unexpected end of image"))?;
+            let name = std::str::from_utf8(&bytes[reader.position..end])
+                .map_err(|_| invalid("symbol name is not UTF-8"))?
+                .to_string();
+            reader.position = end;
+            image.symbols.push((name, reader.usize()?));
+        }
+    }
+
+    let count = reader.usize()?;
+    image.cells.reserve(count.min(bytes.len()));
+    for _ in 0..count
+    {
+        let cell = unzigzag(reader.varint()?);
+        if width(cell) > cell_width
+        {
+            return Err(Error::InvalidImage(format!("cell {} is wider than {} bytes", cell, cell_width)));
+        }
+        image.cells.push(cell);
+    }
+    if reader.position != bytes.len()
+    {
+        return Err(invalid("trailing bytes after the last cell"));
+    }
+
+    Ok(image)
+}
+
+/// Reads either format, telling them apart by the magic header.
+pub fn from_bytes(bytes: &[u8]) -> Result<Image, Error>
+{
+    if is_binary(bytes)
+    {
+        return decode(bytes);
+    }
+
+    let text = std::str::from_utf8(bytes).map_err(|_| invalid("neither a binary image nor UTF-8 text"))?;
+    Ok(Image::new(parse_program(text)?))
+}
+
+pub fn text_to_binary(text: &str) -> Result<Vec<u8>, Error>
+{
+    Ok(encode(&Image::new(parse_program(text)?)))
+}
+
+/// Converts a binary image to the comma separated text format. The entry
+/// point and symbols have no place there and are kept as comments.
+pub fn binary_to_text(bytes: &[u8]) -> Result<String, Error>
+{
+    let image = decode(bytes)?;
+    let mut out = String::new();
+
+    if image.entry != 0
+    {
+        writeln!(out, "# entry {}", image.entry).unwrap();
+    }
+    for (name, address) in &image.symbols
+    {
+        writeln!(out, "# symbol {} {}", name, address).unwrap();
+    }
+    let cells: Vec<String> = image.cells.iter().map(|cell| cell.to_string()).collect();
+    writeln!(out, "{}", cells.join(",")).unwrap();
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_round_trip()
+    {
+        let image = Image
+        {
+            cells: vec![3, 225, 1, -3444, 99, i64::MIN, i64::MAX],
+            entry: 2,
+            symbols: vec![("input".to_string(), 225)],
+        };
+        let bytes = encode(&image);
+        assert_eq!(bytes[5], 8);
+        assert_eq!(decode(&bytes).unwrap(), image);
+
+        let text = binary_to_text(&bytes).unwrap();
+        assert_eq!(from_bytes(text.as_bytes()).unwrap().cells, image.cells);
+        assert_eq!(from_bytes(&text_to_binary("1,0,0,3,99").unwrap()).unwrap(), Image::new(vec![1, 0, 0, 3, 99]));
+
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_varint()
+    {
+        let mut bytes = vec![];
+        write_varint(&mut bytes, u64::MAX);
+        assert_eq!(bytes.len(), 10);
+        assert_eq!(Reader { bytes: &bytes, position: 0 }.varint().unwrap(), u64::MAX);
+
+        bytes[9] = 0x03;
+        assert!(matches!(Reader { bytes: &bytes, position: 0 }.varint(), Err(Error::InvalidImage(_))));
+    }
+}
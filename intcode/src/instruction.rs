@@ -0,0 +1,227 @@
+use std::fmt::Display;
+
+use crate::table::OpcodeTable;
+use crate::Error;
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[repr(i64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opcode
+{
+    ADD = 1,
+    MUL = 2,
+    INPUT = 3,
+    OUTPUT = 4,
+    JMP_EQ = 5,
+    JMP_NE = 6,
+    LESS = 7,
+    EQUAL = 8,
+    ADJUST_BASE = 9,
+    EXIT = 99,
+    /// An opcode registered in an `OpcodeTable`.
+    CUSTOM(i64),
+}
+
+impl Opcode
+{
+    pub const BUILTIN: [Opcode; 10] = [
+        Opcode::ADD, Opcode::MUL, Opcode::INPUT, Opcode::OUTPUT, Opcode::JMP_EQ,
+        Opcode::JMP_NE, Opcode::LESS, Opcode::EQUAL, Opcode::ADJUST_BASE, Opcode::EXIT,
+    ];
+
+    /// The two lowest digits of an instruction using this opcode.
+    pub fn code(&self) -> i64
+    {
+        match self
+        {
+            Opcode::ADD => 1,
+            Opcode::MUL => 2,
+            Opcode::INPUT => 3,
+            Opcode::OUTPUT => 4,
+            Opcode::JMP_EQ => 5,
+            Opcode::JMP_NE => 6,
+            Opcode::LESS => 7,
+            Opcode::EQUAL => 8,
+            Opcode::ADJUST_BASE => 9,
+            Opcode::EXIT => 99,
+            Opcode::CUSTOM(code) => *code,
+        }
+    }
+
+    fn builtin(code: i64) -> Option<Opcode>
+    {
+        Opcode::BUILTIN.into_iter().find(|opcode| opcode.code() == code)
+    }
+
+    /// Parameters of a built-in opcode. Custom opcodes describe theirs in
+    /// the table they are registered in.
+    pub fn params(&self) -> &'static [Param]
+    {
+        match self
+        {
+            Opcode::ADD | Opcode::MUL | Opcode::LESS | Opcode::EQUAL => &[Param::Read, Param::Read, Param::Write],
+            Opcode::JMP_EQ | Opcode::JMP_NE => &[Param::Read, Param::Read],
+            Opcode::INPUT => &[Param::Write],
+            Opcode::OUTPUT | Opcode::ADJUST_BASE => &[Param::Read],
+            Opcode::EXIT | Opcode::CUSTOM(_) => &[],
+        }
+    }
+}
+
+/// Whether a parameter is read, honouring its mode, or names the address
+/// the instruction writes to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Param
+{
+    Read,
+    Write,
+}
+
+impl Display for Opcode
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let text = match self
+        {
+            Opcode::ADD => "ADD",
+            Opcode::MUL => "MUL",
+            Opcode::INPUT => "INPUT",
+            Opcode::OUTPUT => "OUTPUT",
+            Opcode::JMP_EQ => "JUMP_EQ",
+            Opcode::JMP_NE => "JUMP_NE",
+            Opcode::LESS => "LESS",
+            Opcode::EQUAL => "EQUAL",
+            Opcode::ADJUST_BASE => "ADJUST_BASE",
+            Opcode::EXIT => "EXIT",
+            Opcode::CUSTOM(code) => return write!(f, "CUSTOM_{}", code),
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[repr(i64)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode
+{
+    POSITION = 0,
+    IMMEDIATE = 1,
+    /// Like position mode, offset by the machine's relative base.
+    RELATIVE = 2,
+}
+
+impl Mode
+{
+    fn new(value: i64) -> Result<Self, Error>
+    {
+        match value
+        {
+            0 => Ok(Mode::POSITION),
+            1 => Ok(Mode::IMMEDIATE),
+            2 => Ok(Mode::RELATIVE),
+            other => Err(Error::InvalidMode(other)),
+        }
+    }
+}
+
+impl Display for Mode
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        let text = match self
+        {
+            Mode::POSITION => "POSITION",
+            Mode::IMMEDIATE => "IMMEDIATE",
+            Mode::RELATIVE => "RELATIVE",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction
+{
+    pub opcode: Opcode,
+    pub first_param: Mode,
+    pub second_param: Mode,
+    pub third_param: Mode,
+    pub(crate) arity: usize,
+    pub(crate) write_param: Option<usize>,
+}
+
+impl Instruction
+{
+    /// Decodes an instruction using one of the built-in opcodes.
+    pub fn new(value: i64) -> Result<Self, Error>
+    {
+        match Opcode::builtin(value % 100)
+        {
+            Some(opcode) => Instruction::with_params(value, opcode, opcode.params()),
+            None => Err(Error::InvalidOpcode(value)),
+        }
+    }
+
+    pub(crate) fn with_params(value: i64, opcode: Opcode, params: &[Param]) -> Result<Self, Error>
+    {
+        let mut ins = value / 100;
+
+        let first_param = Mode::new(ins % 10)?;
+        ins /= 10;
+        let second_param = Mode::new(ins % 10)?;
+        ins /= 10;
+        let third_param = Mode::new(ins % 10)?;
+
+        Ok(Instruction
+        {
+            opcode,
+            first_param,
+            second_param,
+            third_param,
+            arity: params.len(),
+            write_param: params.iter().position(|&param| param == Param::Write),
+        })
+    }
+
+    /// Number of parameters following the opcode in memory.
+    pub fn arity(&self) -> usize
+    {
+        self.arity
+    }
+
+    /// Index (0-based) of the parameter the instruction writes to, if any.
+    pub fn write_param(&self) -> Option<usize>
+    {
+        self.write_param
+    }
+
+    /// Mode of the parameter at `index` (0-based).
+    pub fn mode(&self, index: usize) -> Mode
+    {
+        match index
+        {
+            0 => self.first_param,
+            1 => self.second_param,
+            _ => self.third_param,
+        }
+    }
+
+    /// Number of memory cells the instruction occupies, opcode included.
+    pub fn size(&self) -> usize
+    {
+        1 + self.arity
+    }
+
+    /// Prints the instruction, naming custom opcodes as registered in
+    /// `table`.
+    pub fn print(&self, table: Option<&OpcodeTable>)
+    {
+        let name = match table
+        {
+            Some(table) => table.name(self.opcode),
+            None => self.opcode.to_string(),
+        };
+        println!("INSTRUCTION: opcode: {}, f_p: {}, s_p: {}, t_p: {}",
+            name, self.first_param, self.second_param, self.third_param);
+    }
+}
@@ -0,0 +1,22 @@
+//! Intcode computer shared by the days that run Intcode programs.
+
+pub mod ascii;
+pub mod assemble;
+pub mod decode;
+pub mod diff;
+mod error;
+pub mod image;
+pub mod instruction;
+pub mod load;
+pub mod optimize;
+pub mod query;
+pub mod record;
+pub mod table;
+pub mod transpile;
+mod vm;
+
+pub use error::Error;
+pub use instruction::{Instruction, Mode, Opcode, Param};
+pub use load::{load_bytes, load_file, parse_program};
+pub use vm::{run_program, run_with_inputs, to_address, State, Vm};
+
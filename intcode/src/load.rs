@@ -0,0 +1,169 @@
+//! Loading Intcode programs from text or binary images.
+//!
+//! Values are separated by commas and may be spread over several lines.
+//! Whitespace around values, a trailing comma at the end of a line, blank
+//! lines and `#` comments running to the end of a line are all ignored.
+//! Files starting with the binary image magic are decoded as images
+//! instead, see `image`.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use crate::image::{self, Image};
+use crate::Error;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadErrorKind
+{
+    MissingValue,
+    InvalidNumber(String),
+    Empty,
+}
+
+/// Where and why a program failed to parse. Lines and columns start at 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadError
+{
+    pub line: usize,
+    pub column: usize,
+    pub kind: LoadErrorKind,
+}
+
+impl Display for LoadError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        match &self.kind
+        {
+            LoadErrorKind::MissingValue => write!(f, "{}:{}: expected a value before ','", self.line, self.column),
+            LoadErrorKind::InvalidNumber(token) => write!(f, "{}:{}: could not convert '{}' to a i64", self.line, self.column, token),
+            LoadErrorKind::Empty => write!(f, "{}:{}: program is empty", self.line, self.column),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+pub fn parse_program(text: &str) -> Result<Vec<i64>, LoadError>
+{
+    let mut program: Vec<i64> = vec![];
+    let mut last_line = 1;
+
+    for (index, line) in text.lines().enumerate()
+    {
+        last_line = index + 1;
+        let code = match line.find('#')
+        {
+            Some(start) => &line[..start],
+            None => line,
+        };
+
+        let pieces: Vec<&str> = code.split(',').collect();
+        let mut offset = 0;
+        for (position, piece) in pieces.iter().enumerate()
+        {
+            let token = piece.trim();
+            let column = column_of(line, offset + piece.len() - piece.trim_start().len());
+            offset += piece.len() + 1;
+
+            if token.is_empty()
+            {
+                // Only the piece after a trailing comma may be empty
+                if position + 1 == pieces.len()
+                {
+                    continue;
+                }
+                return Err(LoadError { line: index + 1, column, kind: LoadErrorKind::MissingValue });
+            }
+
+            match token.parse()
+            {
+                Ok(value) => program.push(value),
+                Err(_) => return Err(LoadError
+                {
+                    line: index + 1,
+                    column,
+                    kind: LoadErrorKind::InvalidNumber(token.to_string()),
+                }),
+            }
+        }
+    }
+
+    if program.is_empty()
+    {
+        return Err(LoadError { line: last_line, column: 1, kind: LoadErrorKind::Empty });
+    }
+
+    Ok(program)
+}
+
+/// 1-based column of the byte at `offset` in `line`.
+fn column_of(line: &str, offset: usize) -> usize
+{
+    line[..offset].chars().count() + 1
+}
+
+/// Loads a program in either the text or the binary image format.
+pub fn load_image(path: impl AsRef<Path>) -> Result<Image, Error>
+{
+    image::from_bytes(&fs::read(path)?)
+}
+
+/// Loads the cells of a program in either the text or the binary image
+/// format.
+pub fn load_file(path: impl AsRef<Path>) -> Result<Vec<i64>, Error>
+{
+    cells(load_image(path)?)
+}
+
+/// The cells of a program already read into memory, in either the text or
+/// the binary image format.
+pub fn load_bytes(bytes: &[u8]) -> Result<Vec<i64>, Error>
+{
+    cells(image::from_bytes(bytes)?)
+}
+
+/// The cells of an image that starts at address 0. Images with another
+/// entry point are refused instead of being started at 0, they need
+/// `load_image` and `Vm::with_pc`.
+fn cells(image: Image) -> Result<Vec<i64>, Error>
+{
+    if image.entry != 0
+    {
+        return Err(Error::InvalidImage(format!("entry point is {}, load it with load_image", image.entry)));
+    }
+
+    Ok(image.cells)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_parse_program()
+    {
+        let text = "# day 5 example\n3,0, 4,0,\n  99 # halt\n\n";
+        assert_eq!(parse_program(text), Ok(vec![3, 0, 4, 0, 99]));
+
+        let error = parse_program("1,2\n3,,4").unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (2, 3, LoadErrorKind::MissingValue));
+
+        let error = parse_program("1,2,\n  x3,4").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        assert_eq!(parse_program("# nothing\n").unwrap_err().kind, LoadErrorKind::Empty);
+    }
+
+    #[test]
+    fn test_load_bytes()
+    {
+        let mut image = Image::new(vec![1101, 2, 3, 0, 99]);
+        assert_eq!(load_bytes(&image::encode(&image)).unwrap(), image.cells);
+
+        image.entry = 4;
+        assert!(matches!(load_bytes(&image::encode(&image)), Err(Error::InvalidImage(_))));
+    }
+}
@@ -0,0 +1,648 @@
+//! Peephole optimizer for Intcode images.
+//!
+//! The decoded program is split into basic blocks. A block holding a cell
+//! the program writes to is left alone, as is anything that reads an
+//! instruction as data. Every other cell that is never written holds its
+//! image value for the whole run, and the optimizer
+//!
+//! - turns position-mode reads of such constant cells into immediates and
+//!   folds arithmetic whose operands are both known,
+//! - rewrites conditional jumps whose condition is known to be taken into
+//!   the canonical unconditional `1105,1,target`,
+//! - removes jumps to the next instruction, shifting everything after them
+//!   down and relocating every address that refers past them.
+//!
+//! A written opcode cell is decoded past as every built-in instruction it
+//! could become. Still, a written instruction can turn into anything, and
+//! a jump to a computed address can land anywhere, so once either shows up
+//! control flow may leave the decoded code. Every cell outside it is then
+//! swept for instructions that could read or write, and no jump is
+//! removed, as addresses computed at run time can't be relocated.
+//!
+//! The relative base is assumed to address data, the stack of compiled
+//! programs, so cells outside the decoded code are not constant in a
+//! program that writes through it. Where it points is only known at run
+//! time, so no jump is removed from a program that uses it either.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use crate::decode::{decode_from, Listing};
+use crate::instruction::{Instruction, Mode, Opcode};
+use crate::table::OpcodeTable;
+use crate::vm::{State, Vm};
+use crate::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind
+{
+    Folded,
+    Unconditional,
+    Removed,
+}
+
+/// One rewritten instruction. `address` is the address in the original
+/// image and `after` is the instruction before relocation, empty for
+/// removed instructions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change
+{
+    pub address: usize,
+    pub kind: Kind,
+    pub before: Vec<i64>,
+    pub after: Vec<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report
+{
+    pub changes: Vec<Change>,
+    /// Why the program was left untouched, if it was.
+    pub skipped: Option<String>,
+    /// Original address ranges of the blocks left alone because the
+    /// program writes into them.
+    pub excluded: Vec<(usize, usize)>,
+    /// Original address ranges that were removed from the image.
+    removed: Vec<(usize, usize)>,
+}
+
+impl Report
+{
+    /// Address in the optimized image of the cell at `address` in the
+    /// original one. Removed cells map to whatever follows them.
+    pub fn relocate(&self, address: usize) -> usize
+    {
+        let shift: usize = self.removed
+            .iter()
+            .filter(|&&(start, _)| start < address)
+            .map(|&(start, end)| end.min(address) - start)
+            .sum();
+
+        address - shift
+    }
+}
+
+impl Display for Report
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        if let Some(reason) = &self.skipped
+        {
+            return writeln!(f, "not optimized: {}", reason);
+        }
+
+        for (start, end) in &self.excluded
+        {
+            writeln!(f, "{:<6} [{}..{}] is written while running", "keep", start, end)?;
+        }
+        for change in &self.changes
+        {
+            let kind = match change.kind
+            {
+                Kind::Folded => "fold",
+                Kind::Unconditional => "jump",
+                Kind::Removed => "remove",
+            };
+            writeln!(f, "{:<6} [{}] {} => {}", kind, change.address, join(&change.before), join(&change.after))?;
+        }
+        writeln!(f, "{} instruction(s) changed", self.changes.len())
+    }
+}
+
+fn join(cells: &[i64]) -> String
+{
+    cells.iter().map(|cell| cell.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn encode(opcode: Opcode, modes: &[Mode], operands: &[i64]) -> Vec<i64>
+{
+    let mut value = opcode.code();
+    for (index, mode) in modes.iter().enumerate()
+    {
+        value += (*mode as i64) * 10_i64.pow(index as u32 + 2);
+    }
+
+    std::iter::once(value).chain(operands.iter().copied()).collect()
+}
+
+fn is_jump(opcode: Opcode) -> bool
+{
+    matches!(opcode, Opcode::JMP_EQ | Opcode::JMP_NE)
+}
+
+/// Addresses the instruction at `address` refers to through its
+/// parameters: position-mode operands and immediate jump targets.
+fn references(program: &[i64], address: usize, instruction: &Instruction) -> Vec<(usize, usize)>
+{
+    (0..instruction.arity())
+        .filter(|&index|
+            instruction.mode(index) == Mode::POSITION
+                || Some(index) == instruction.write_param()
+                || is_jump(instruction.opcode) && index == 1)
+        .filter_map(|index| usize::try_from(program[address + 1 + index]).ok().map(|target| (index, target)))
+        .collect()
+}
+
+/// Why the program can't be optimized at all, if it can't.
+fn unsafe_reason(program: &[i64], listing: &Listing) -> Option<String>
+{
+    let negative = listing.instructions.iter().any(|(&address, instruction)|
+        (0..instruction.arity()).any(|index|
+            (instruction.mode(index) == Mode::POSITION || Some(index) == instruction.write_param())
+                && program[address + 1 + index] < 0));
+    if negative
+    {
+        return Some("it refers to negative addresses".to_string());
+    }
+
+    None
+}
+
+/// Cells the instruction at `address` reads through a position-mode
+/// parameter, and the cell it writes to unless that goes through the
+/// relative base.
+fn accesses(program: &[i64], address: usize, instruction: &Instruction) -> (Vec<usize>, Vec<usize>)
+{
+    let mut reads = vec![];
+    let mut writes = vec![];
+    for index in 0..instruction.arity()
+    {
+        let target = match usize::try_from(program[address + 1 + index])
+        {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        if Some(index) == instruction.write_param()
+        {
+            if instruction.mode(index) != Mode::RELATIVE
+            {
+                writes.push(target);
+            }
+        }
+        else if instruction.mode(index) == Mode::POSITION
+        {
+            reads.push(target);
+        }
+    }
+
+    (reads, writes)
+}
+
+/// Reads and writes of every instruction that could start at a cell
+/// outside the decoded code.
+fn sweep(program: &[i64], listing: &Listing) -> (BTreeSet<usize>, BTreeSet<usize>)
+{
+    let mut reads = BTreeSet::new();
+    let mut writes = BTreeSet::new();
+    for address in (0..program.len()).filter(|&address| !listing.is_code(address))
+    {
+        if let Ok(instruction) = Instruction::new(program[address])
+            && address + instruction.size() <= program.len()
+        {
+            let (read, written) = accesses(program, address, &instruction);
+            reads.extend(read);
+            writes.extend(written);
+        }
+    }
+
+    (reads, writes)
+}
+
+/// Where execution might go on from the opcode cell at `address` once the
+/// program has written into it: past every built-in instruction it could
+/// become with its parameter modes kept, and to the target of the jumps.
+fn patched_successors(program: &[i64], address: usize) -> Vec<usize>
+{
+    let modes = program[address] / 100 * 100;
+    let mut successors = vec![];
+    for opcode in Opcode::BUILTIN
+    {
+        let instruction = match Instruction::new(modes + opcode.code())
+        {
+            Ok(instruction) if opcode != Opcode::EXIT => instruction,
+            _ => continue,
+        };
+        successors.push(address + instruction.size());
+        if is_jump(opcode)
+            && instruction.second_param == Mode::IMMEDIATE
+            && let Some(target) = program.get(address + 2).and_then(|&target| usize::try_from(target).ok())
+        {
+            successors.push(target);
+        }
+    }
+
+    successors.retain(|&address| address < program.len());
+    successors
+}
+
+/// The decoded code, with the cells that might be read as data and the
+/// cells that might be written.
+struct Analysis
+{
+    listing: Listing,
+    reads: BTreeSet<usize>,
+    written: BTreeSet<usize>,
+    /// True if control flow can leave the decoded code.
+    escapes: bool,
+}
+
+/// Decodes the program from address 0, and again from wherever a written
+/// opcode cell could send execution, until that finds nothing new.
+fn analyse(program: &[i64]) -> Analysis
+{
+    let table = OpcodeTable::new();
+    let mut entries = BTreeSet::from([0]);
+    loop
+    {
+        let listing = decode_from(program, &table, &entries.iter().copied().collect::<Vec<_>>());
+        let escapes = listing.dynamic_jumps || listing.is_self_modifying();
+        let (mut reads, mut written) = if escapes
+        {
+            sweep(program, &listing)
+        }
+        else
+        {
+            (BTreeSet::new(), BTreeSet::new())
+        };
+        for (&address, instruction) in &listing.instructions
+        {
+            let (read, writes) = accesses(program, address, instruction);
+            reads.extend(read);
+            written.extend(writes);
+        }
+
+        let patched: Vec<usize> = written
+            .iter()
+            .filter(|address| listing.instructions.contains_key(address) || listing.invalid.contains(address))
+            .flat_map(|&address| patched_successors(program, address))
+            .filter(|address| !entries.contains(address))
+            .collect();
+        if patched.is_empty()
+        {
+            return Analysis { listing, reads, written, escapes };
+        }
+        entries.extend(patched);
+    }
+}
+
+/// Basic blocks of the listing, as the addresses of their instructions. A
+/// block ends at a jump, at an exit and at an instruction the program
+/// writes into, since that one might become either.
+fn blocks(program: &[i64], listing: &Listing, written: &BTreeSet<usize>) -> Vec<Vec<usize>>
+{
+    let is_written = |address: usize, instruction: &Instruction|
+        (address..address + instruction.size()).any(|cell| written.contains(&cell));
+
+    let mut leaders: BTreeSet<usize> = BTreeSet::from([0]);
+    for (&address, instruction) in &listing.instructions
+    {
+        if is_jump(instruction.opcode) || is_written(address, instruction)
+        {
+            leaders.insert(address + instruction.size());
+            leaders.extend(listing.successors(program, address));
+        }
+    }
+
+    let mut blocks: Vec<Vec<usize>> = vec![];
+    let mut next = None;
+    for (&address, instruction) in &listing.instructions
+    {
+        match blocks.last_mut()
+        {
+            Some(block) if next == Some(address) && !leaders.contains(&address) => block.push(address),
+            _ => blocks.push(vec![address]),
+        }
+        next = match instruction.opcode
+        {
+            Opcode::EXIT => None,
+            _ => Some(address + instruction.size()),
+        };
+    }
+
+    blocks
+}
+
+pub fn optimize(program: &[i64]) -> (Vec<i64>, Report)
+{
+    let Analysis { listing, reads, written, escapes } = analyse(program);
+    let mut report = Report::default();
+
+    if let Some(reason) = unsafe_reason(program, &listing)
+    {
+        report.skipped = Some(reason);
+        return (program.to_vec(), report);
+    }
+    let relative_writes = listing.instructions
+        .values()
+        .any(|instruction| instruction.write_param().is_some_and(|index| instruction.mode(index) == Mode::RELATIVE));
+
+    // Instructions in a block that is written to keep their cells, and so
+    // do instructions decoded over each other from different entries.
+    let mut owners = vec![0; program.len()];
+    for (&address, instruction) in &listing.instructions
+    {
+        for owner in &mut owners[address..address + instruction.size()]
+        {
+            *owner += 1;
+        }
+    }
+    let mut excluded: BTreeSet<usize> = listing.instructions
+        .iter()
+        .filter(|&(&address, instruction)| owners[address..address + instruction.size()].iter().any(|&owner| owner > 1))
+        .map(|(&address, _)| address)
+        .collect();
+    for block in blocks(program, &listing, &written)
+    {
+        let start = block[0];
+        let end = block.last().map(|address| address + listing.instructions[address].size()).unwrap_or(start);
+        if (start..end).any(|cell| written.contains(&cell))
+        {
+            report.excluded.push((start, end));
+            excluded.extend(block);
+        }
+    }
+
+    let constant = |address: usize| -> Option<i64>
+    {
+        if written.contains(&address) || (relative_writes && !listing.is_code(address))
+        {
+            return None;
+        }
+        Some(program.get(address).copied().unwrap_or(0))
+    };
+
+    let mut image = program.to_vec();
+    let mut candidates = vec![];
+
+    for (&address, instruction) in &listing.instructions
+    {
+        // Cells read as data by some instruction must keep their value.
+        let size = instruction.size();
+        if excluded.contains(&address) || (address..address + size).any(|cell| reads.contains(&cell))
+        {
+            continue;
+        }
+
+        let arity = instruction.arity();
+        let mut modes: Vec<Mode> = (0..arity).map(|index| instruction.mode(index)).collect();
+        let mut operands: Vec<i64> = program[address + 1..address + size].to_vec();
+
+        for index in 0..arity
+        {
+            if modes[index] == Mode::POSITION && Some(index) != instruction.write_param()
+                && let Some(value) = constant(operands[index] as usize)
+            {
+                modes[index] = Mode::IMMEDIATE;
+                operands[index] = value;
+            }
+        }
+
+        let mut opcode = instruction.opcode;
+        let mut kind = Kind::Folded;
+        let both_known = arity >= 2 && modes[0] == Mode::IMMEDIATE && modes[1] == Mode::IMMEDIATE;
+
+        match opcode
+        {
+            Opcode::ADD | Opcode::MUL | Opcode::LESS | Opcode::EQUAL if both_known =>
+            {
+                let (a, b) = (operands[0], operands[1]);
+                let result = match opcode
+                {
+                    Opcode::ADD => a.checked_add(b),
+                    Opcode::MUL => a.checked_mul(b),
+                    Opcode::LESS => Some((a < b) as i64),
+                    _ => Some((a == b) as i64),
+                };
+                if let Some(result) = result
+                {
+                    opcode = Opcode::ADD;
+                    operands[0] = result;
+                    operands[1] = 0;
+                }
+            }
+            Opcode::JMP_EQ | Opcode::JMP_NE =>
+            {
+                let next = (address + size) as i64;
+                if modes[1] == Mode::IMMEDIATE && operands[1] == next
+                {
+                    candidates.push(address);
+                    continue;
+                }
+                if modes[0] == Mode::IMMEDIATE && (operands[0] != 0) == (opcode == Opcode::JMP_EQ)
+                {
+                    opcode = Opcode::JMP_EQ;
+                    operands[0] = 1;
+                    kind = Kind::Unconditional;
+                }
+            }
+            _ => {}
+        }
+
+        let after = encode(opcode, &modes, &operands);
+        if after[..] != program[address..address + size]
+        {
+            image[address..address + size].copy_from_slice(&after);
+            report.changes.push(Change { address, kind, before: program[address..address + size].to_vec(), after });
+        }
+    }
+
+    // Base adjustments and relative operands may point into the image, and
+    // those can't be relocated
+    if !escapes && !listing.relative
+    {
+        remove_jumps(&listing, &mut image, &candidates, &mut report);
+    }
+    report.changes.sort_by_key(|change| change.address);
+
+    (image, report)
+}
+
+/// Removes the jumps at `candidates` from `image`, as long as nothing reads
+/// or writes their cells, and relocates every reference in the remaining
+/// instructions.
+fn remove_jumps(listing: &Listing, image: &mut Vec<i64>, candidates: &[usize], report: &mut Report)
+{
+    let decoded: Vec<(usize, Instruction)> = listing.instructions
+        .keys()
+        .map(|&address| (address, Instruction::new(image[address]).unwrap()))
+        .collect();
+
+    // Jumping to the start of a removed jump is fine, it lands on whatever
+    // follows it just like the jump would have. Anything else that refers
+    // into it keeps it in place.
+    let mut touched: BTreeSet<usize> = BTreeSet::new();
+    let mut targets: BTreeSet<usize> = BTreeSet::new();
+    for (address, instruction) in &decoded
+    {
+        for (index, target) in references(image, *address, instruction)
+        {
+            if is_jump(instruction.opcode) && index == 1
+            {
+                targets.insert(target);
+            }
+            else
+            {
+                touched.insert(target);
+            }
+        }
+    }
+
+    for &address in candidates
+    {
+        if (address..address + 3).any(|cell| touched.contains(&cell)) || targets.contains(&(address + 1)) || targets.contains(&(address + 2))
+        {
+            continue;
+        }
+        report.removed.push((address, address + 3));
+        report.changes.push(Change { address, kind: Kind::Removed, before: image[address..address + 3].to_vec(), after: vec![] });
+    }
+    if report.removed.is_empty()
+    {
+        return;
+    }
+
+    let removed = |address: usize| report.removed.iter().any(|&(start, end)| start <= address && address < end);
+    let mut relocated = image.clone();
+    for (address, instruction) in &decoded
+    {
+        if removed(*address)
+        {
+            continue;
+        }
+        for (index, target) in references(image, *address, instruction)
+        {
+            relocated[address + 1 + index] = report.relocate(target) as i64;
+        }
+    }
+
+    *image = relocated
+        .into_iter()
+        .enumerate()
+        .filter(|&(address, _)| !removed(address))
+        .map(|(_, cell)| cell)
+        .collect();
+}
+
+/// Result of running a program on a fixed list of inputs.
+#[derive(Debug, PartialEq, Eq)]
+struct Run
+{
+    output: Vec<i64>,
+    state: State,
+    memory: Vec<i64>,
+}
+
+fn run(program: &[i64], inputs: &[i64]) -> Result<Run, Error>
+{
+    let mut vm = Vm::new(program.to_vec());
+    for &value in inputs
+    {
+        vm.push_input(value);
+    }
+    let state = vm.run()?;
+
+    Ok(Run { output: vm.take_output(), state, memory: vm.memory })
+}
+
+/// Runs both images on the same inputs and checks that they produce the
+/// same output, stop for the same reason and leave every data cell, that is
+/// every cell outside the original's instructions, with the same value.
+pub fn equivalent(original: &[i64], optimized: &[i64], report: &Report, inputs: &[i64]) -> Result<bool, Error>
+{
+    let before = run(original, inputs)?;
+    let after = run(optimized, inputs)?;
+
+    if before.output != after.output || before.state != after.state
+    {
+        return Ok(false);
+    }
+
+    let listing = analyse(original).listing;
+    let read = |memory: &[i64], address: usize| memory.get(address).copied().unwrap_or(0);
+    let same_memory = (0..before.memory.len())
+        .filter(|&address| !listing.is_code(address))
+        .all(|address| read(&before.memory, address) == read(&after.memory, report.relocate(address)));
+
+    Ok(same_memory)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_optimize()
+    {
+        let program = vec![
+            3, 20,              // 0: INPUT [20]
+            1, 21, 22, 23,      // 2: ADD [21] [22] => [23], both constant
+            1105, 1, 9,         // 6: jump to the next instruction
+            1106, 0, 13,        // 9: always taken
+            99,                 // 12
+            1, 20, 23, 24,      // 13: ADD [20] [23] => [24]
+            4, 24,              // 17: OUTPUT [24]
+            99,                 // 19
+            0, 5, 6, 0, 0,      // 20: data
+        ];
+
+        let (optimized, report) = optimize(&program);
+        assert!(report.skipped.is_none());
+        assert_eq!(report.changes.iter().map(|c| (c.address, c.kind)).collect::<Vec<_>>(),
+            vec![(2, Kind::Folded), (6, Kind::Removed), (9, Kind::Unconditional)]);
+        assert_eq!(optimized.len(), program.len() - 3);
+        assert_eq!(&optimized[2..6], &[1101, 11, 0, 20]);
+
+        for input in [-3, 0, 42]
+        {
+            assert!(equivalent(&program, &optimized, &report, &[input]).unwrap());
+        }
+
+        // Day 2 example writes into its only block
+        let (_, report) = optimize(&[1, 1, 1, 4, 99, 5, 6, 0, 99]);
+        assert!(report.changes.is_empty());
+        assert_eq!(report.excluded, vec![(0, 5)]);
+    }
+
+    #[test]
+    fn test_optimize_relative()
+    {
+        let program = vec![
+            109, 14,            // 0: ADJUST_BASE 14, pointing at the data
+            1105, 1, 5,         // 2: jump to the next instruction
+            204, 0,             // 5: OUTPUT [base+0]
+            99,                 // 7
+            0, 0, 0, 0, 0, 0,   // 8: padding
+            42,                 // 14: data
+        ];
+
+        let (optimized, report) = optimize(&program);
+        assert!(report.changes.iter().all(|change| change.kind != Kind::Removed));
+        assert_eq!(optimized.len(), program.len());
+        assert!(equivalent(&program, &optimized, &report, &[]).unwrap());
+    }
+
+    #[test]
+    fn test_optimize_around_written_block()
+    {
+        let program = vec![
+            1001, 4, 1, 4,      // 0: ADD [4] 1 => [4], turns the ADD at 4 into a MUL
+            1, 17, 18, 19,      // 4: MUL [17] [18] => [19] once patched
+            1, 20, 21, 22,      // 8: ADD [20] [21] => [22], both constant
+            4, 19,              // 12: OUTPUT [19]
+            4, 22,              // 14: OUTPUT [22]
+            99,                 // 16
+            3, 7, 0, 5, 6, 0,   // 17: data
+        ];
+
+        let (optimized, report) = optimize(&program);
+        assert!(report.skipped.is_none());
+        assert_eq!(report.excluded, vec![(0, 8)]);
+        assert_eq!(report.changes.iter().map(|c| (c.address, c.kind)).collect::<Vec<_>>(), vec![(8, Kind::Folded)]);
+        assert_eq!(&optimized[..8], &program[..8]);
+        assert_eq!(&optimized[8..12], &[1101, 11, 0, 22]);
+
+        assert_eq!(run(&optimized, &[]).unwrap().output, vec![21, 11]);
+        assert!(equivalent(&program, &optimized, &report, &[]).unwrap());
+    }
+}
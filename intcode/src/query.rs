@@ -0,0 +1,72 @@
+//! Repeated runs of a program that answers one question per run.
+//!
+//! Some programs read a query, print the answer and halt, so every query
+//! needs a fresh machine. `Query` keeps the pristine image next to a single
+//! machine and only restores the cells the previous run wrote.
+
+use crate::vm::{State, Vm};
+use crate::Error;
+
+pub struct Query
+{
+    image: Vec<i64>,
+    vm: Vm,
+    /// Number of queries asked so far.
+    pub runs: usize,
+}
+
+impl Query
+{
+    pub fn new(image: Vec<i64>) -> Self
+    {
+        let mut vm = Vm::new(image.clone());
+        vm.track_writes();
+
+        Query { image, vm, runs: 0 }
+    }
+
+    /// Runs the program from the start on `inputs` and returns everything
+    /// it printed. Fails if it asks for more input than that.
+    pub fn ask(&mut self, inputs: &[i64]) -> Result<Vec<i64>, Error>
+    {
+        if self.runs > 0
+        {
+            self.vm.reset(&self.image);
+        }
+        self.runs += 1;
+
+        for &value in inputs
+        {
+            self.vm.push_input(value);
+        }
+        match self.vm.run()?
+        {
+            State::Halted => Ok(self.vm.take_output()),
+            State::NeedInput => Err(Error::InputExhausted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_query()
+    {
+        // Stores its input past the end of the image and prints it doubled
+        let image = vec![3, 20, 1002, 20, 2, 20, 4, 20, 99];
+        let mut query = Query::new(image.clone());
+
+        for value in [1, 5, -3]
+        {
+            assert_eq!(query.ask(&[value]).unwrap(), vec![2 * value]);
+        }
+        assert_eq!(query.runs, 3);
+        assert!(matches!(query.ask(&[]), Err(Error::InputExhausted)));
+
+        query.vm.reset(&query.image);
+        assert_eq!(query.vm.memory, image);
+    }
+}
@@ -0,0 +1,229 @@
+//! Execution log for record-and-replay.
+//!
+//! A recording `Vm` appends one `Step` per executed instruction with
+//! everything needed to undo it, which is what `Vm::step_back` uses. The
+//! log can be written out as text and replayed against the same program
+//! elsewhere, failing loudly if the run does not repeat itself.
+
+use std::fmt::Display;
+use std::rc::Rc;
+
+use crate::table::OpcodeTable;
+use crate::vm::Vm;
+use crate::Error;
+
+const HEADER: &str = "intcode-log 1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Write
+{
+    pub address: usize,
+    pub old: i64,
+    pub new: i64,
+}
+
+/// One executed instruction. Every instruction writes at most one cell,
+/// reads at most one input, produces at most one output and moves the
+/// relative base at most once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step
+{
+    pub pc: usize,
+    pub next_pc: usize,
+    pub input: Option<i64>,
+    pub write: Option<Write>,
+    pub output: Option<i64>,
+    /// Amount added to the relative base.
+    pub adjust: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Log
+{
+    pub steps: Vec<Step>,
+}
+
+impl Log
+{
+    /// Every input the recorded run consumed, in order.
+    pub fn inputs(&self) -> Vec<i64>
+    {
+        self.steps.iter().filter_map(|step| step.input).collect()
+    }
+
+    /// Every write to `address` as `(step index, step)`, oldest first.
+    pub fn history(&self, address: usize) -> impl Iterator<Item = (usize, &Step)>
+    {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter(move |(_, step)| step.write.is_some_and(|write| write.address == address))
+    }
+
+    /// The last step that wrote `address`, if any.
+    pub fn last_write(&self, address: usize) -> Option<(usize, &Step)>
+    {
+        self.history(address).last()
+    }
+
+    pub fn parse(text: &str) -> Result<Log, Error>
+    {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(HEADER)
+        {
+            return Err(Error::Parse(format!("missing '{}' header", HEADER)));
+        }
+
+        let mut steps = vec![];
+        for line in lines.filter(|line| !line.trim().is_empty())
+        {
+            let mut tokens = line.split_whitespace();
+            let mut step = Step
+            {
+                pc: parse_token(tokens.next(), line)?,
+                next_pc: parse_token(tokens.next(), line)?,
+                input: None,
+                write: None,
+                output: None,
+                adjust: None,
+            };
+
+            for token in tokens
+            {
+                let mut chars = token.chars();
+                let kind = chars.next();
+                let value = chars.as_str();
+                match kind
+                {
+                    Some('i') => step.input = Some(parse_token(Some(value), line)?),
+                    Some('o') => step.output = Some(parse_token(Some(value), line)?),
+                    Some('b') => step.adjust = Some(parse_token(Some(value), line)?),
+                    Some('w') =>
+                    {
+                        let mut parts = value.split(':');
+                        step.write = Some(Write
+                        {
+                            address: parse_token(parts.next(), line)?,
+                            old: parse_token(parts.next(), line)?,
+                            new: parse_token(parts.next(), line)?,
+                        });
+                    }
+                    _ => return Err(Error::Parse(line.to_string())),
+                }
+            }
+            steps.push(step);
+        }
+
+        Ok(Log { steps })
+    }
+}
+
+fn parse_token<T: std::str::FromStr>(token: Option<&str>, line: &str) -> Result<T, Error>
+{
+    token
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| Error::Parse(line.to_string()))
+}
+
+/// One line per step: `pc next_pc [i<input>] [w<address>:<old>:<new>] [o<output>] [b<adjust>]`.
+impl Display for Log
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        writeln!(f, "{}", HEADER)?;
+        for step in &self.steps
+        {
+            write!(f, "{} {}", step.pc, step.next_pc)?;
+            if let Some(value) = step.input
+            {
+                write!(f, " i{}", value)?;
+            }
+            if let Some(write) = step.write
+            {
+                write!(f, " w{}:{}:{}", write.address, write.old, write.new)?;
+            }
+            if let Some(value) = step.output
+            {
+                write!(f, " o{}", value)?;
+            }
+            if let Some(value) = step.adjust
+            {
+                write!(f, " b{}", value)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `program` again on the inputs recorded in `log`, checking that
+/// every step does exactly what was recorded. The returned machine is
+/// recording, so it can be stepped backwards from the end of the run.
+pub fn replay(program: Vec<i64>, log: &Log) -> Result<Vm, Error>
+{
+    replay_on(Vm::new(program), log)
+}
+
+/// Like `replay`, for a program recorded with the custom opcodes in
+/// `table`.
+pub fn replay_with(program: Vec<i64>, log: &Log, table: Rc<OpcodeTable>) -> Result<Vm, Error>
+{
+    replay_on(Vm::with_table(program, table), log)
+}
+
+fn replay_on(mut vm: Vm, log: &Log) -> Result<Vm, Error>
+{
+    vm.record();
+    for value in log.inputs()
+    {
+        vm.push_input(value);
+    }
+
+    for (index, expected) in log.steps.iter().enumerate()
+    {
+        vm.step()?;
+        if vm.log().and_then(|log| log.steps.last()) != Some(expected)
+        {
+            return Err(Error::ReplayDiverged(index));
+        }
+    }
+
+    Ok(vm)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn test_record_and_replay()
+    {
+        // Compares the input to 8, from the day 5 puzzle text
+        let program = vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let mut vm = Vm::new(program.clone());
+        vm.record();
+        vm.push_input(8);
+        assert_eq!(vm.run().unwrap(), State::Halted);
+        assert_eq!(vm.output(), &[1]);
+
+        let log = Log::parse(&vm.log().unwrap().to_string()).unwrap();
+        assert_eq!(&log, vm.log().unwrap());
+        assert_eq!(log.last_write(9).map(|(index, _)| index), Some(1));
+
+        while vm.step_back() {}
+        assert_eq!(vm.memory, program);
+        assert_eq!(vm.pc, 0);
+        assert!(vm.output().is_empty());
+
+        let replayed = replay(program.clone(), &log).unwrap();
+        assert_eq!(replayed.output(), &[1]);
+        assert!(replayed.is_halted());
+
+        let mut diverging = program;
+        diverging[10] = 7;
+        assert!(matches!(replay(diverging, &log), Err(Error::ReplayDiverged(1))));
+    }
+}
@@ -0,0 +1,273 @@
+//! Registry of opcodes, so experimental instructions can be added without
+//! touching the interpreter.
+//!
+//! The built-in opcodes are always present and run natively. Custom opcodes
+//! declare their parameters and a handler; the machine resolves the
+//! parameters (values for reads, addresses for writes) before calling the
+//! handler and applies the `Effect` it returns, so custom instructions are
+//! traced and recorded like any other. `decode_with`, `disassemble`,
+//! `assemble`, `replay_with` and `transpile_with` take the same table.
+//!
+//! ```
+//! use intcode::table::{Effect, OpcodeTable};
+//! use intcode::instruction::Param;
+//! use intcode::Vm;
+//! use std::rc::Rc;
+//!
+//! let mut table = OpcodeTable::new();
+//! table.register(42, "HALT_WITH", &[Param::Read], |args| Ok(Effect::halt_with(args[0]))).unwrap();
+//!
+//! let mut vm = Vm::with_table(vec![1101, 2, 3, 7, 42, 7, 99, 0], Rc::new(table));
+//! vm.run().unwrap();
+//! assert_eq!(vm.output(), &[5]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::instruction::{Instruction, Opcode, Param};
+use crate::Error;
+
+/// What a custom instruction does once its handler has run. Unless it jumps
+/// or halts, execution continues with the next instruction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Effect
+{
+    /// Value to store at the instruction's write parameter.
+    pub write: Option<i64>,
+    pub output: Option<i64>,
+    pub jump: Option<usize>,
+    pub halt: bool,
+}
+
+impl Effect
+{
+    pub fn next() -> Self
+    {
+        Effect::default()
+    }
+
+    pub fn write(value: i64) -> Self
+    {
+        Effect { write: Some(value), ..Effect::default() }
+    }
+
+    pub fn output(value: i64) -> Self
+    {
+        Effect { output: Some(value), ..Effect::default() }
+    }
+
+    pub fn jump(address: usize) -> Self
+    {
+        Effect { jump: Some(address), ..Effect::default() }
+    }
+
+    pub fn halt() -> Self
+    {
+        Effect { halt: true, ..Effect::default() }
+    }
+
+    /// Outputs `code` and halts.
+    pub fn halt_with(code: i64) -> Self
+    {
+        Effect { output: Some(code), halt: true, ..Effect::default() }
+    }
+}
+
+/// Called with one argument per parameter: the value for reads, the
+/// address for the write.
+pub type Handler = Rc<dyn Fn(&[i64]) -> Result<Effect, Error>>;
+
+#[derive(Clone)]
+pub struct Definition
+{
+    pub name: String,
+    pub params: Vec<Param>,
+    /// None for the built-in opcodes, which the machine runs itself.
+    pub handler: Option<Handler>,
+}
+
+#[derive(Clone)]
+pub struct OpcodeTable
+{
+    definitions: BTreeMap<i64, Definition>,
+}
+
+impl Default for OpcodeTable
+{
+    fn default() -> Self
+    {
+        OpcodeTable::new()
+    }
+}
+
+impl Debug for OpcodeTable
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        f.debug_map()
+            .entries(self.definitions.iter().map(|(code, definition)| (code, &definition.name)))
+            .finish()
+    }
+}
+
+impl OpcodeTable
+{
+    /// A table holding only the built-in opcodes.
+    pub fn new() -> Self
+    {
+        let definitions = Opcode::BUILTIN
+            .iter()
+            .map(|opcode| (opcode.code(), Definition
+            {
+                name: opcode.to_string(),
+                params: opcode.params().to_vec(),
+                handler: None,
+            }))
+            .collect();
+
+        OpcodeTable { definitions }
+    }
+
+    pub fn register<F>(&mut self, code: i64, name: &str, params: &[Param], handler: F) -> Result<(), Error>
+        where F: Fn(&[i64]) -> Result<Effect, Error> + 'static
+    {
+        let reason = if !(1..100).contains(&code)
+        {
+            Some("opcodes are two digits")
+        }
+        else if self.definitions.contains_key(&code)
+        {
+            Some("opcode is already registered")
+        }
+        else if name.is_empty() || self.by_name(name).is_some()
+        {
+            Some("name is empty or already taken")
+        }
+        else if params.len() > 3
+        {
+            Some("at most three parameters are supported")
+        }
+        else if params.iter().filter(|&&param| param == Param::Write).count() > 1
+        {
+            Some("at most one parameter can be written")
+        }
+        else
+        {
+            None
+        };
+        if let Some(reason) = reason
+        {
+            return Err(Error::InvalidDefinition(format!("{} ({}): {}", name, code, reason)));
+        }
+
+        self.definitions.insert(code, Definition
+        {
+            name: name.to_string(),
+            params: params.to_vec(),
+            handler: Some(Rc::new(handler)),
+        });
+
+        Ok(())
+    }
+
+    pub fn get(&self, code: i64) -> Option<&Definition>
+    {
+        self.definitions.get(&code)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<(i64, &Definition)>
+    {
+        self.definitions
+            .iter()
+            .find(|(_, definition)| definition.name == name)
+            .map(|(&code, definition)| (code, definition))
+    }
+
+    /// Name of the opcode, as registered.
+    pub fn name(&self, opcode: Opcode) -> String
+    {
+        match self.get(opcode.code())
+        {
+            Some(definition) => definition.name.clone(),
+            None => opcode.to_string(),
+        }
+    }
+
+    pub fn decode(&self, value: i64) -> Result<Instruction, Error>
+    {
+        let code = value % 100;
+        let definition = self.get(code).ok_or(Error::InvalidOpcode(value))?;
+        let opcode = match definition.handler
+        {
+            Some(_) => Opcode::CUSTOM(code),
+            None => Instruction::new(value)?.opcode,
+        };
+
+        Instruction::with_params(value, opcode, &definition.params)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::assemble::assemble;
+    use crate::decode::{decode_with, disassemble};
+    use crate::record::{replay, replay_with};
+    use crate::Vm;
+
+    const SOURCE: &str = "
+        INPUT [14]
+        DOUBLE [14] [15]
+        JUMP_NEG [15] 11
+        OUTPUT [15]
+        EXIT
+        OUTPUT 0
+        EXIT
+        DATA 0
+        DATA 0
+    ";
+
+    fn table() -> Rc<OpcodeTable>
+    {
+        let mut table = OpcodeTable::new();
+        table.register(20, "DOUBLE", &[Param::Read, Param::Write], |args| Ok(Effect::write(args[0] * 2))).unwrap();
+        table.register(21, "JUMP_NEG", &[Param::Read, Param::Read], |args|
+            Ok(if args[0] < 0 { Effect::jump(args[1] as usize) } else { Effect::next() })).unwrap();
+
+        Rc::new(table)
+    }
+
+    #[test]
+    fn test_custom_opcodes()
+    {
+        let table = table();
+        let program = assemble(SOURCE, &table).unwrap();
+        assert_eq!(&program[..8], &[3, 14, 20, 14, 15, 1021, 15, 11]);
+
+        for (input, output) in [(3, 6), (-2, 0)]
+        {
+            let mut vm = Vm::with_table(program.clone(), Rc::clone(&table));
+            vm.push_input(input);
+            vm.run().unwrap();
+            assert_eq!(vm.output(), &[output]);
+        }
+
+        // The jump target is only known to the handler
+        let listing = decode_with(&program, &table);
+        assert!(listing.dynamic_jumps);
+        assert_eq!(listing.instructions.keys().copied().collect::<Vec<_>>(), vec![0, 2, 5, 8, 10]);
+        assert!(listing.writes.contains(&15));
+        assert!(disassemble(&program, &table).contains("    2: DOUBLE [14] [15]\n"));
+
+        let mut vm = Vm::with_table(program.clone(), Rc::clone(&table));
+        vm.record();
+        vm.push_input(-2);
+        vm.run().unwrap();
+        let log = vm.log().unwrap();
+        assert_eq!(replay_with(program.clone(), log, table).unwrap().output(), &[0]);
+        assert!(matches!(replay(program, log), Err(Error::InvalidOpcode(20))));
+    }
+}
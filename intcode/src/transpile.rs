@@ -0,0 +1,272 @@
+//! Ahead-of-time translation of an Intcode image into Rust source.
+//!
+//! Every statically decoded instruction becomes an arm of a `match pc`, so
+//! jumps are plain state transitions. Operand addresses are baked into the
+//! generated code, which is only valid as long as the program does not
+//! modify its own instructions: a write that changes a code cell, an
+//! unknown jump target or a patched image all hand the machine over to the
+//! interpreter, so the generated function always behaves like
+//! `run_program`.
+//!
+//! From a day crate's `build.rs`:
+//!
+//! ```ignore
+//! fn main()
+//! {
+//!     intcode::transpile::build("data.txt", "data").unwrap();
+//! }
+//! ```
+//!
+//! and in the crate itself:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/data.rs"));
+//!
+//! let output = data(&mut program, &[1])?;
+//! ```
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::decode::{decode, decode_with, Listing};
+use crate::instruction::{Instruction, Mode, Opcode};
+use crate::table::OpcodeTable;
+use crate::vm::{State, Vm};
+use crate::{load_file, Error};
+
+/// Generates a function `name(memory: &mut Vec<i64>, input: &[i64]) ->
+/// Result<Vec<i64>, intcode::Error>` that runs `program`.
+pub fn transpile(program: &[i64], name: &str) -> String
+{
+    generate(program, &decode(program), name, None)
+}
+
+/// Like `transpile`, for a program using the custom opcodes in `table`.
+/// Custom instructions run in the interpreter, so the generated function
+/// takes the table as a third parameter, `table: &Rc<OpcodeTable>`, which
+/// has to hold the same opcodes.
+pub fn transpile_with(program: &[i64], name: &str, table: &OpcodeTable) -> String
+{
+    generate(program, &decode_with(program, table), name, Some(table))
+}
+
+fn generate(program: &[i64], listing: &Listing, name: &str, table: Option<&OpcodeTable>) -> String
+{
+    let (parameters, fallback) = match table
+    {
+        Some(_) => (", table: &::std::rc::Rc<::intcode::table::OpcodeTable>", "::intcode::transpile::fallback_with(table, memory, "),
+        None => ("", "::intcode::transpile::fallback(memory, "),
+    };
+    let mut out = String::new();
+
+    let code: Vec<String> = (0..program.len())
+        .filter(|&address| listing.is_code(address))
+        .map(|address| format!("({}, {})", address, program[address]))
+        .collect();
+
+    writeln!(out, "#[allow(unused_mut, unused_variables, unused_assignments, unused_parens, unreachable_code, clippy::all)]").unwrap();
+    writeln!(out, "pub fn {}(memory: &mut Vec<i64>, input: &[i64]{}) -> Result<Vec<i64>, ::intcode::Error>", name, parameters).unwrap();
+    writeln!(out, "{{").unwrap();
+    writeln!(out, "    const LEN: usize = {};", program.len()).unwrap();
+    writeln!(out, "    const CODE: [(usize, i64); {}] = [{}];", code.len(), code.join(", ")).unwrap();
+    writeln!(out, "    let mut output: Vec<i64> = vec![];").unwrap();
+    writeln!(out, "    let mut next_input: usize = 0;").unwrap();
+    writeln!(out, "    if memory.len() < LEN || CODE.iter().any(|&(address, value)| memory[address] != value)").unwrap();
+    writeln!(out, "    {{").unwrap();
+    writeln!(out, "        return {}0, input, output);", fallback).unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    let mut pc: usize = 0;").unwrap();
+    writeln!(out, "    loop").unwrap();
+    writeln!(out, "    {{").unwrap();
+    writeln!(out, "        match pc").unwrap();
+    writeln!(out, "        {{").unwrap();
+    for (&address, instruction) in &listing.instructions
+    {
+        writeln!(out, "            {} =>", address).unwrap();
+        writeln!(out, "            {{").unwrap();
+        emit_instruction(&mut out, program, listing, address, instruction, fallback);
+        writeln!(out, "            }}").unwrap();
+    }
+    writeln!(out, "            _ => return {}pc, &input[next_input..], output),", fallback).unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    out
+}
+
+/// Writes the body of the arm for the instruction at `address`. `fallback`
+/// is the call handing the machine over to the interpreter, up to its `pc`
+/// argument.
+fn emit_instruction(out: &mut String, program: &[i64], listing: &Listing, address: usize, instruction: &Instruction, fallback: &str)
+{
+    const INDENT: &str = "                ";
+    let next = address + instruction.size();
+    let raw = |index: usize| program[address + 1 + index];
+
+    // Negative addresses are left to the interpreter so the error matches,
+    // and so is everything involving the relative base, which is only known
+    // at run time.
+    let operands_valid = (0..instruction.arity()).all(|index|
+    {
+        let is_address = instruction.mode(index) == Mode::POSITION || Some(index) == instruction.write_param();
+        instruction.mode(index) != Mode::RELATIVE && (!is_address || raw(index) >= 0)
+    });
+    if !operands_valid || instruction.opcode == Opcode::ADJUST_BASE
+    {
+        writeln!(out, "{}return {}pc, &input[next_input..], output);", INDENT, fallback).unwrap();
+        return;
+    }
+
+    let operand = |index: usize| -> String
+    {
+        match instruction.mode(index)
+        {
+            Mode::IMMEDIATE => format!("({}i64)", raw(index)),
+            Mode::POSITION if (raw(index) as usize) < program.len() => format!("memory[{}]", raw(index)),
+            Mode::POSITION | Mode::RELATIVE => format!("::intcode::transpile::load(memory, {})", raw(index)),
+        }
+    };
+    let store = |out: &mut String, target: usize|
+    {
+        if target < program.len()
+        {
+            writeln!(out, "{}memory[{}] = value;", INDENT, target).unwrap();
+        }
+        else
+        {
+            writeln!(out, "{}::intcode::transpile::store(memory, {}, value);", INDENT, target).unwrap();
+        }
+        if listing.is_code(target)
+        {
+            writeln!(out, "{}if value != {}i64", INDENT, program[target]).unwrap();
+            writeln!(out, "{}{{", INDENT).unwrap();
+            writeln!(out, "{}    return {}{}, &input[next_input..], output);", INDENT, fallback, next).unwrap();
+            writeln!(out, "{}}}", INDENT).unwrap();
+        }
+    };
+
+    match instruction.opcode
+    {
+        Opcode::ADD | Opcode::MUL | Opcode::LESS | Opcode::EQUAL =>
+        {
+            let expression = match instruction.opcode
+            {
+                Opcode::ADD => format!("{} + {}", operand(0), operand(1)),
+                Opcode::MUL => format!("{} * {}", operand(0), operand(1)),
+                Opcode::LESS => format!("({} < {}) as i64", operand(0), operand(1)),
+                _ => format!("({} == {}) as i64", operand(0), operand(1)),
+            };
+            writeln!(out, "{}let value = {};", INDENT, expression).unwrap();
+            store(out, raw(2) as usize);
+            writeln!(out, "{}pc = {};", INDENT, next).unwrap();
+        }
+        Opcode::INPUT =>
+        {
+            writeln!(out, "{}if next_input == input.len()", INDENT).unwrap();
+            writeln!(out, "{}{{", INDENT).unwrap();
+            writeln!(out, "{}    return Err(::intcode::Error::InputExhausted);", INDENT).unwrap();
+            writeln!(out, "{}}}", INDENT).unwrap();
+            writeln!(out, "{}let value = input[next_input];", INDENT).unwrap();
+            writeln!(out, "{}next_input += 1;", INDENT).unwrap();
+            store(out, raw(0) as usize);
+            writeln!(out, "{}pc = {};", INDENT, next).unwrap();
+        }
+        Opcode::OUTPUT =>
+        {
+            writeln!(out, "{}output.push({});", INDENT, operand(0)).unwrap();
+            writeln!(out, "{}pc = {};", INDENT, next).unwrap();
+        }
+        Opcode::JMP_EQ | Opcode::JMP_NE =>
+        {
+            let test = if instruction.opcode == Opcode::JMP_EQ { "!=" } else { "==" };
+            let target = match instruction.second_param
+            {
+                Mode::IMMEDIATE if raw(1) >= 0 => format!("{}", raw(1)),
+                _ => format!("::intcode::to_address({})?", operand(1)),
+            };
+            writeln!(out, "{}pc = if {} {} 0 {{ {} }} else {{ {} }};", INDENT, operand(0), test, target, next).unwrap();
+        }
+        Opcode::EXIT =>
+        {
+            writeln!(out, "{}return Ok(output);", INDENT).unwrap();
+        }
+        Opcode::ADJUST_BASE | Opcode::CUSTOM(_) =>
+        {
+            writeln!(out, "{}return {}pc, &input[next_input..], output);", INDENT, fallback).unwrap();
+        }
+    }
+}
+
+/// Reads the text program at `source` and writes the generated function to
+/// `destination`.
+pub fn transpile_file(source: impl AsRef<Path>, destination: impl AsRef<Path>, name: &str) -> Result<(), Error>
+{
+    let program = load_file(source)?;
+    fs::write(destination, transpile(&program, name))?;
+
+    Ok(())
+}
+
+/// Entry point for build scripts: transpiles `source` (relative to the crate
+/// root) into `$OUT_DIR/<name>.rs` and asks cargo to rerun when it changes.
+pub fn build(source: &str, name: &str) -> Result<(), Error>
+{
+    let out_dir = env::var("OUT_DIR")
+        .map_err(|_| Error::Io(std::io::Error::other("OUT_DIR is not set, call this from a build script")))?;
+    println!("cargo:rerun-if-changed={}", source);
+
+    transpile_file(source, Path::new(&out_dir).join(format!("{}.rs", name)), name)
+}
+
+/// Continues a run in the interpreter. Called by generated code whenever it
+/// can no longer trust what it was compiled from.
+pub fn fallback(memory: &mut Vec<i64>, pc: usize, input: &[i64], output: Vec<i64>) -> Result<Vec<i64>, Error>
+{
+    interpret(Vm::with_pc(std::mem::take(memory), pc), memory, input, output)
+}
+
+/// Like `fallback`, for code generated by `transpile_with`.
+pub fn fallback_with(table: &Rc<OpcodeTable>, memory: &mut Vec<i64>, pc: usize, input: &[i64], output: Vec<i64>) -> Result<Vec<i64>, Error>
+{
+    let mut vm = Vm::with_table(std::mem::take(memory), Rc::clone(table));
+    vm.pc = pc;
+
+    interpret(vm, memory, input, output)
+}
+
+fn interpret(mut vm: Vm, memory: &mut Vec<i64>, input: &[i64], mut output: Vec<i64>) -> Result<Vec<i64>, Error>
+{
+    for &value in input
+    {
+        vm.push_input(value);
+    }
+    let result = vm.run();
+    output.extend(vm.take_output());
+    *memory = vm.memory;
+
+    match result?
+    {
+        State::Halted => Ok(output),
+        State::NeedInput => Err(Error::InputExhausted),
+    }
+}
+
+/// Read of an address past the end of the compiled image.
+pub fn load(memory: &[i64], address: usize) -> i64
+{
+    memory.get(address).copied().unwrap_or(0)
+}
+
+/// Write to an address past the end of the compiled image.
+pub fn store(memory: &mut Vec<i64>, address: usize, value: i64)
+{
+    if address >= memory.len()
+    {
+        memory.resize(address + 1, 0);
+    }
+    memory[address] = value;
+}
@@ -0,0 +1,530 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::instruction::{Instruction, Mode, Opcode};
+use crate::record::{Log, Step, Write};
+use crate::table::OpcodeTable;
+use crate::Error;
+
+/// Why a call to `Vm::run` returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State
+{
+    Halted,
+    NeedInput,
+}
+
+/// The cells written since the last reset, each listed once however often
+/// it is written.
+#[derive(Clone, Debug, Default)]
+struct Dirty
+{
+    addresses: Vec<usize>,
+    seen: Vec<bool>,
+}
+
+impl Dirty
+{
+    fn mark(&mut self, address: usize)
+    {
+        if address >= self.seen.len()
+        {
+            self.seen.resize(address + 1, false);
+        }
+        if !self.seen[address]
+        {
+            self.seen[address] = true;
+            self.addresses.push(address);
+        }
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = usize> + '_
+    {
+        let seen = &mut self.seen;
+        self.addresses.drain(..).inspect(move |&address| seen[address] = false)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Vm
+{
+    pub memory: Vec<i64>,
+    pub pc: usize,
+    pub relative_base: i64,
+    pub trace: bool,
+    input: VecDeque<i64>,
+    output: Vec<i64>,
+    halted: bool,
+    log: Option<Log>,
+    /// Addresses written since tracking started, see `reset`.
+    dirty: Option<Dirty>,
+    /// Opcodes beyond the built-in ones, if any were registered.
+    table: Option<Rc<OpcodeTable>>,
+}
+
+impl Vm
+{
+    pub fn new(memory: Vec<i64>) -> Self
+    {
+        Vm::with_pc(memory, 0)
+    }
+
+    /// Creates a machine that resumes execution at `pc` instead of at the
+    /// start of the program.
+    pub fn with_pc(memory: Vec<i64>, pc: usize) -> Self
+    {
+        Vm { memory, pc, relative_base: 0, trace: false, input: VecDeque::new(), output: vec![], halted: false, log: None, dirty: None, table: None }
+    }
+
+    /// Creates a machine that also understands the custom opcodes in
+    /// `table`.
+    pub fn with_table(memory: Vec<i64>, table: Rc<OpcodeTable>) -> Self
+    {
+        let mut vm = Vm::new(memory);
+        vm.table = Some(table);
+
+        vm
+    }
+
+    pub fn table(&self) -> Option<&OpcodeTable>
+    {
+        self.table.as_deref()
+    }
+
+    /// Decodes the instruction at `address` with this machine's opcodes.
+    pub fn instruction_at(&self, address: usize) -> Result<Instruction, Error>
+    {
+        match &self.table
+        {
+            Some(table) => table.decode(self.read(address)),
+            None => Instruction::new(self.read(address)),
+        }
+    }
+
+    pub fn push_input(&mut self, value: i64)
+    {
+        self.input.push_back(value);
+    }
+
+    pub fn output(&self) -> &[i64]
+    {
+        &self.output
+    }
+
+    pub fn take_output(&mut self) -> Vec<i64>
+    {
+        std::mem::take(&mut self.output)
+    }
+
+    pub fn is_halted(&self) -> bool
+    {
+        self.halted
+    }
+
+    /// Starts recording every executed instruction, see `step_back`.
+    pub fn record(&mut self)
+    {
+        self.log.get_or_insert_with(Log::default);
+    }
+
+    pub fn log(&self) -> Option<&Log>
+    {
+        self.log.as_ref()
+    }
+
+    pub fn take_log(&mut self) -> Option<Log>
+    {
+        self.log.take()
+    }
+
+    /// Starts remembering which cells get written, so `reset` only has to
+    /// restore those.
+    pub fn track_writes(&mut self)
+    {
+        self.dirty.get_or_insert_with(Dirty::default);
+    }
+
+    /// Puts the machine back in its initial state for `image`, the program
+    /// it was created from. With `track_writes` on this takes time
+    /// proportional to the number of cells written since the last reset rather
+    /// than to the size of the image. Recording and tracking stay on.
+    pub fn reset(&mut self, image: &[i64])
+    {
+        match &mut self.dirty
+        {
+            Some(dirty) =>
+            {
+                self.memory.truncate(image.len());
+                for address in dirty.drain()
+                {
+                    if let Some(&value) = image.get(address)
+                    {
+                        self.memory[address] = value;
+                    }
+                }
+            }
+            None => self.memory = image.to_vec(),
+        }
+        if let Some(log) = &mut self.log
+        {
+            log.steps.clear();
+        }
+
+        self.pc = 0;
+        self.relative_base = 0;
+        self.input.clear();
+        self.output.clear();
+        self.halted = false;
+    }
+
+    /// Undoes the last recorded instruction: restores the cell it wrote,
+    /// hands back the input it consumed, drops the output it produced and
+    /// moves `pc` back. Returns false if there is nothing left to undo.
+    pub fn step_back(&mut self) -> bool
+    {
+        let step = match self.log.as_mut().and_then(|log| log.steps.pop())
+        {
+            Some(step) => step,
+            None => return false,
+        };
+
+        if let Some(write) = step.write
+        {
+            self.memory[write.address] = write.old;
+        }
+        if let Some(value) = step.input
+        {
+            self.input.push_front(value);
+        }
+        if step.output.is_some()
+        {
+            self.output.pop();
+        }
+        if let Some(adjust) = step.adjust
+        {
+            self.relative_base -= adjust;
+        }
+        self.pc = step.pc;
+        self.halted = false;
+
+        true
+    }
+
+    /// Runs until the program halts or needs input that has not been pushed
+    /// yet. On error `pc` is left pointing at the faulting instruction.
+    pub fn run(&mut self) -> Result<State, Error>
+    {
+        loop
+        {
+            if let Some(state) = self.step()?
+            {
+                return Ok(state);
+            }
+        }
+    }
+
+    /// Executes a single instruction. Returns `None` if execution can
+    /// continue.
+    pub fn step(&mut self) -> Result<Option<State>, Error>
+    {
+        if self.halted
+        {
+            return Ok(Some(State::Halted));
+        }
+
+        let index = self.pc;
+        let instruction = self.instruction_at(index)?;
+        if self.trace
+        {
+            println!("INDEX: {}", index);
+            instruction.print(self.table());
+        }
+        let mut step = Step { pc: index, next_pc: index, input: None, write: None, output: None, adjust: None };
+
+        match instruction.opcode
+        {
+            Opcode::ADD | Opcode::MUL | Opcode::LESS | Opcode::EQUAL =>
+            {
+                let val1 = self.param(&instruction, 0)?;
+                let val2 = self.param(&instruction, 1)?;
+                let index3 = self.address(&instruction, 2)?;
+
+                let result = match instruction.opcode
+                {
+                    Opcode::ADD => val1 + val2,
+                    Opcode::MUL => val1 * val2,
+                    Opcode::LESS => (val1 < val2) as i64,
+                    _ => (val1 == val2) as i64,
+                };
+                step.write = Some(self.store(index3, result));
+
+                if self.trace
+                {
+                    println!("{} {} {} => {}({})", instruction.opcode, val1, val2, result, index3);
+                }
+                self.pc += 4;
+            }
+            Opcode::INPUT =>
+            {
+                let index1 = self.address(&instruction, 0)?;
+                let value = match self.input.pop_front()
+                {
+                    Some(value) => value,
+                    None => return Ok(Some(State::NeedInput)),
+                };
+                step.input = Some(value);
+                step.write = Some(self.store(index1, value));
+
+                if self.trace
+                {
+                    println!("INPUT {} => [{}]", value, index1);
+                }
+                self.pc += 2;
+            }
+            Opcode::OUTPUT =>
+            {
+                let val1 = self.param(&instruction, 0)?;
+                self.output.push(val1);
+                step.output = Some(val1);
+
+                if self.trace
+                {
+                    println!("OUTPUT => {}", val1);
+                }
+                self.pc += 2;
+            }
+            Opcode::JMP_EQ | Opcode::JMP_NE =>
+            {
+                let val1 = self.param(&instruction, 0)?;
+                let taken = (val1 != 0) == (instruction.opcode == Opcode::JMP_EQ);
+
+                if taken
+                {
+                    let val2 = self.param(&instruction, 1)?;
+                    self.pc = to_address(val2)?;
+                }
+                else
+                {
+                    self.pc += 3;
+                }
+
+                if self.trace
+                {
+                    println!("{} ({}) {} => [{}]", instruction.opcode, if taken { "YES" } else { "NO" }, val1, self.pc);
+                }
+            }
+            Opcode::ADJUST_BASE =>
+            {
+                let val1 = self.param(&instruction, 0)?;
+                self.relative_base += val1;
+                step.adjust = Some(val1);
+
+                if self.trace
+                {
+                    println!("ADJUST_BASE {} => {}", val1, self.relative_base);
+                }
+                self.pc += 2;
+            }
+            Opcode::CUSTOM(code) =>
+            {
+                let table = self.table.clone().ok_or(Error::InvalidOpcode(code))?;
+                let definition = table.get(code).ok_or(Error::InvalidOpcode(code))?;
+                let handler = definition.handler.as_ref().ok_or(Error::InvalidOpcode(code))?;
+
+                let mut args = Vec::with_capacity(instruction.arity());
+                for index in 0..instruction.arity()
+                {
+                    if Some(index) == instruction.write_param()
+                    {
+                        args.push(self.address(&instruction, index)? as i64);
+                    }
+                    else
+                    {
+                        args.push(self.param(&instruction, index)?);
+                    }
+                }
+
+                let effect = handler(&args)?;
+                if let (Some(value), Some(index)) = (effect.write, instruction.write_param())
+                {
+                    step.write = Some(self.store(args[index] as usize, value));
+                }
+                if let Some(value) = effect.output
+                {
+                    self.output.push(value);
+                    step.output = Some(value);
+                }
+
+                if self.trace
+                {
+                    println!("{} {:?} => {:?}", definition.name, args, effect);
+                }
+                if effect.halt
+                {
+                    self.halted = true;
+                }
+                else
+                {
+                    self.pc = match effect.jump
+                    {
+                        Some(address) => address,
+                        None => self.pc + instruction.size(),
+                    };
+                }
+            }
+            Opcode::EXIT =>
+            {
+                if self.trace
+                {
+                    println!("EXIT");
+                }
+                self.halted = true;
+            }
+        }
+
+        step.next_pc = self.pc;
+        if let Some(log) = &mut self.log
+        {
+            log.steps.push(step);
+        }
+
+        Ok(if self.halted { Some(State::Halted) } else { None })
+    }
+
+    /// Reads a memory cell, treating everything past the end as zero.
+    pub fn read(&self, address: usize) -> i64
+    {
+        self.memory.get(address).copied().unwrap_or(0)
+    }
+
+    /// Writes a memory cell, growing memory if needed.
+    pub fn write(&mut self, address: usize, value: i64)
+    {
+        self.store(address, value);
+    }
+
+    fn store(&mut self, address: usize, value: i64) -> Write
+    {
+        if address >= self.memory.len()
+        {
+            self.memory.resize(address + 1, 0);
+        }
+        let old = std::mem::replace(&mut self.memory[address], value);
+        if let Some(dirty) = &mut self.dirty
+        {
+            dirty.mark(address);
+        }
+
+        Write { address, old, new: value }
+    }
+
+    /// Value of the parameter at `index` (0-based) of the instruction at `pc`.
+    fn param(&self, instruction: &Instruction, index: usize) -> Result<i64, Error>
+    {
+        let raw = self.read(self.pc + 1 + index);
+        match instruction.mode(index)
+        {
+            Mode::POSITION => Ok(self.read(to_address(raw)?)),
+            Mode::IMMEDIATE => Ok(raw),
+            Mode::RELATIVE => Ok(self.read(to_address(self.relative_base + raw)?)),
+        }
+    }
+
+    /// Address a write parameter at `index` (0-based) refers to. Writes
+    /// never use immediate mode, it is treated as position mode.
+    fn address(&self, instruction: &Instruction, index: usize) -> Result<usize, Error>
+    {
+        let raw = self.read(self.pc + 1 + index);
+        match instruction.mode(index)
+        {
+            Mode::RELATIVE => to_address(self.relative_base + raw),
+            _ => to_address(raw),
+        }
+    }
+}
+
+pub fn to_address(value: i64) -> Result<usize, Error>
+{
+    usize::try_from(value).map_err(|_| Error::InvalidAddress(value))
+}
+
+/// Runs `program` to completion, answering every INPUT instruction with
+/// `input`, and returns everything the program printed.
+pub fn run_program(program: &mut Vec<i64>, input: i64) -> Result<Vec<i64>, Error>
+{
+    let mut vm = Vm::new(std::mem::take(program));
+    let result = loop
+    {
+        match vm.run()
+        {
+            Ok(State::Halted) => break Ok(vm.take_output()),
+            Ok(State::NeedInput) => vm.push_input(input),
+            Err(e) => break Err(e),
+        }
+    };
+    *program = vm.memory;
+
+    result
+}
+
+/// Runs `program` to completion on the given inputs, in order, and returns
+/// everything the program printed. Fails if the program asks for more.
+pub fn run_with_inputs(program: &mut Vec<i64>, inputs: &[i64]) -> Result<Vec<i64>, Error>
+{
+    let mut vm = Vm::new(std::mem::take(program));
+    for &value in inputs
+    {
+        vm.push_input(value);
+    }
+    let result = vm.run();
+    *program = vm.memory;
+
+    match result?
+    {
+        State::Halted => Ok(vm.output),
+        State::NeedInput => Err(Error::InputExhausted),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_relative_base()
+    {
+        // Outputs a copy of itself, from the day 9 puzzle text
+        let quine = vec![109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99];
+        let mut vm = Vm::new(quine.clone());
+        vm.record();
+        assert_eq!(vm.run().unwrap(), State::Halted);
+        assert_eq!(vm.output(), &quine[..]);
+
+        while vm.step_back() {}
+        assert_eq!(vm.relative_base, 0);
+        assert_eq!(vm.memory[..quine.len()], quine[..]);
+
+        let mut program = vec![104, 1125899906842624, 99];
+        assert_eq!(run_with_inputs(&mut program, &[]).unwrap(), vec![1125899906842624]);
+    }
+
+    #[test]
+    fn test_reset_tracks_each_cell_once()
+    {
+        // Counts cell 10 down from 5 to 0, writing it five times
+        let image = vec![1001, 10, -1, 10, 1005, 10, 0, 99, 0, 0, 5];
+        let mut vm = Vm::new(image.clone());
+        vm.track_writes();
+        for _ in 0..3
+        {
+            assert_eq!(vm.run().unwrap(), State::Halted);
+            assert_eq!(vm.memory[10], 0);
+            assert_eq!(vm.dirty.as_ref().unwrap().addresses, vec![10]);
+
+            vm.reset(&image);
+            assert_eq!(vm.memory, image);
+            assert!(vm.dirty.as_ref().unwrap().addresses.is_empty());
+        }
+    }
+}
@@ -1,63 +1,44 @@
-use std::fs;
-use std::io::BufRead;
-use std::process;
-use std::time::Instant;
+use aoc_common::{input, Answer, Error, Solution};
 
-use clap::Parser;
+pub struct Day00;
 
-const DATA_FILE_PATH: &str = "data.txt";
-const TEST_DATA_FILE_PATH: &str = "test_data.txt";
-
-#[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-struct Args
+impl Solution for Day00
 {
-    #[arg(short, long, default_value_t=false)]
-    debug: bool,
+    type Input = Vec<i64>;
 
-    #[arg(short, long, default_value_t=false)]
-    part_two: bool,
-}
+    const DIRECTORY: &'static str = env!("CARGO_MANIFEST_DIR");
 
+    fn parse(&self, text: &str) -> Result<Self::Input, Error>
+    {
+        // PARSE THE INPUT HERE
+        Ok(input::numbers(text)?)
+    }
 
-fn main()
-{
-    let args = Args::parse();
+    fn part_one(&self, _input: &Self::Input) -> Result<Answer, Error>
+    {
+        Err(Error::Solve("part one is not solved yet".to_string()))
+    }
 
-    println!("Starting...");
-    let filename = if args.debug { TEST_DATA_FILE_PATH } else { DATA_FILE_PATH };
-    let file = match fs::File::open(filename)
+    fn part_two(&self, _input: &Self::Input) -> Result<Answer, Error>
     {
-        Ok(f) => f,
-        Err(e) => 
-        {
-            eprintln!("Error: could not load file {}, {}", filename, e);
-            process::exit(1);
-        }
-    };
-    let reader = std::io::BufReader::new(file);
+        Err(Error::Solve("part two is not solved yet".to_string()))
+    }
+}
 
-    let start_time = Instant::now();
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoc_common::examples;
 
-    for result in reader.lines()
+    #[test]
+    fn test_examples()
     {
-        match result
+        let examples = examples::load(Day00::DIRECTORY).unwrap().unwrap_or_default();
+        for outcome in examples::check(&Day00, examples)
         {
-            Ok(line) =>
-            {
-                // PROCESS THE LINE INPUT HERE
-            },
-            Err(e) => 
-            {
-                eprintln!("Error: could not read line from file, {}", e);
-                process::exit(1);
-            }
+            assert!(outcome.passed(), "example {} part {} gave {:?}, expected {}",
+                outcome.example.name, outcome.example.part, outcome.actual, outcome.example.answer);
         }
     }
-
-    // DO THE PROCESSING OF THE DATA HERE
-
-    let duration = start_time.elapsed();
-
-    println!("Finished running in: {:.3?}", duration);
 }